@@ -0,0 +1,99 @@
+//! Measures `RuleIndex` lookups as the rule set grows.
+//!
+//! Run with `cargo bench --bench rule_index`. Lookup time should stay roughly flat from
+//! 100 to 10k rules while the linear scan grows with the rule count.
+
+use desktop_lib::domain::models::{Action, Condition, Rule, RulePolicy};
+use desktop_lib::rules::{find_matching_rule, LinkFacts, RuleIndex};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const INDEXED_LOOKUPS: u32 = 2_000;
+const LINEAR_LOOKUPS: u32 = 20;
+
+fn rule(index: usize) -> Rule {
+    let (operator, value) = match index % 20 {
+        0 => (
+            "regex",
+            format!(r"^https://portal{index}\.corp\.test/app/\d+"),
+        ),
+        1..=9 => ("host", format!("service{index}.example{}.com", index % 97)),
+        _ => (
+            "wildcard",
+            format!("*.team{index}.example{}.net/*", index % 89),
+        ),
+    };
+
+    Rule {
+        id: format!("rule-{index}"),
+        rule_name: value.clone(),
+        priority: index as i32,
+        conditions: vec![Condition {
+            fact: "url".to_string(),
+            operator: operator.to_string(),
+            value,
+        }],
+        action: Action {
+            profile: String::new(),
+            browser: "firefox__default".to_string(),
+            url: String::new(),
+            policy: RulePolicy::Always,
+        },
+        enabled: true,
+    }
+}
+
+fn probes(count: usize) -> Vec<LinkFacts> {
+    [
+        format!(
+            "https://service{}.example{}.com/home",
+            count - 9,
+            (count - 9) % 97
+        ),
+        format!(
+            "https://a.team{}.example{}.net/board",
+            count - 5,
+            (count - 5) % 89
+        ),
+        format!("https://portal{}.corp.test/app/42", count - 20),
+        "https://unmatched.example.org/".to_string(),
+    ]
+    .iter()
+    .map(|url| LinkFacts::from_url(url).expect("valid url"))
+    .collect()
+}
+
+fn time_per_lookup(iterations: u32, probes: &[LinkFacts], lookup: impl Fn(&LinkFacts)) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        for facts in probes {
+            lookup(black_box(facts));
+        }
+    }
+    start.elapsed() / (iterations * probes.len() as u32)
+}
+
+fn main() {
+    println!(
+        "{:>8} {:>12} {:>14} {:>14}",
+        "rules", "build", "indexed/op", "linear/op"
+    );
+
+    for count in [100, 1_000, 10_000] {
+        let rules = (0..count).map(rule).collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let index = RuleIndex::build(&rules);
+        let build = start.elapsed();
+
+        let probes = probes(count);
+        let indexed = time_per_lookup(INDEXED_LOOKUPS, &probes, |facts| {
+            black_box(index.find(facts));
+        });
+        let linear = time_per_lookup(LINEAR_LOOKUPS, &probes, |facts| {
+            black_box(find_matching_rule(&rules, facts));
+        });
+
+        println!("{count:>8} {build:>12.2?} {indexed:>14.2?} {linear:>14.2?}");
+    }
+}
@@ -0,0 +1,276 @@
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An installed browser as the frontend, rules and preferences refer to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserDescriptor {
+    /// Stays the same when the browser is renamed; a second install of it gets its own id.
+    pub id: String,
+    pub display_name: String,
+    pub family: BrowserFamily,
+    pub executable: Option<PathBuf>,
+    pub version: Option<String>,
+    pub channel: ReleaseChannel,
+    pub icon: Option<PathBuf>,
+}
+
+/// Rendering engine a browser is built on, which decides its profile and launch arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserFamily {
+    Chromium,
+    Gecko,
+    Webkit,
+    Unknown,
+}
+
+const CHROMIUM_WORDS: [&str; 14] = [
+    "chrome",
+    "chromium",
+    "edge",
+    "brave",
+    "vivaldi",
+    "opera",
+    "thorium",
+    "arc",
+    "yandex",
+    "cromite",
+    "supermium",
+    "helium",
+    "sidekick",
+    "ungoogled",
+];
+const GECKO_WORDS: [&str; 11] = [
+    "firefox",
+    "waterfox",
+    "librewolf",
+    "floorp",
+    "zen",
+    "mullvad",
+    "tor",
+    "iceweasel",
+    "icecat",
+    "seamonkey",
+    "palemoon",
+];
+const WEBKIT_WORDS: [&str; 4] = ["safari", "orion", "epiphany", "webkit"];
+
+impl BrowserFamily {
+    /// Guesses the engine from a browser or executable name such as `google-chrome-stable`.
+    pub fn from_name(name: &str) -> Self {
+        let words = name_words(name);
+        let has = |list: &[&str]| words.iter().any(|word| list.contains(&word.as_str()));
+        if has(&CHROMIUM_WORDS) {
+            BrowserFamily::Chromium
+        } else if has(&GECKO_WORDS) {
+            BrowserFamily::Gecko
+        } else if has(&WEBKIT_WORDS) || words.concat() == "gnomeweb" {
+            BrowserFamily::Webkit
+        } else {
+            BrowserFamily::Unknown
+        }
+    }
+}
+
+/// Release channel of an install, read from its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    Stable,
+    Beta,
+    Dev,
+    Canary,
+    Nightly,
+    Esr,
+}
+
+impl ReleaseChannel {
+    pub fn from_name(name: &str) -> Self {
+        let words = name_words(name);
+        let has = |word: &str| words.iter().any(|w| w == word);
+        if has("canary") || has("sxs") {
+            ReleaseChannel::Canary
+        } else if has("nightly") {
+            ReleaseChannel::Nightly
+        } else if has("dev") || has("developer") || has("unstable") {
+            ReleaseChannel::Dev
+        } else if has("beta") || has("preview") {
+            ReleaseChannel::Beta
+        } else if has("esr") {
+            ReleaseChannel::Esr
+        } else {
+            ReleaseChannel::Stable
+        }
+    }
+}
+
+fn name_words(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Lowercase ascii letters and digits with single dashes in between.
+pub fn slugify(value: &str) -> String {
+    let lowered = value.to_lowercase();
+    let mut slug = String::with_capacity(lowered.len());
+    for c in lowered.chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// Builds a stable id from where a browser was found, e.g. `["flatpak", "org.mozilla.firefox"]`.
+pub fn stable_id(parts: &[&str]) -> String {
+    slugify(&parts.join("-"))
+}
+
+/// Finds the browser an id refers to, by stable id or by the slug of its display name that
+/// older rules and preferences stored.
+pub fn find_descriptor<'a>(
+    descriptors: &'a [BrowserDescriptor],
+    id: &str,
+) -> Option<&'a BrowserDescriptor> {
+    descriptors
+        .iter()
+        .find(|descriptor| descriptor.id == id)
+        .or_else(|| {
+            descriptors
+                .iter()
+                .find(|descriptor| slugify(&descriptor.display_name) == id)
+        })
+}
+
+/// Rewrites a `<browser>__<profile>` catalog id to use the stable id of the browser.
+///
+/// Returns `None` when no installed browser matches, so the id can be kept as it was.
+pub fn canonical_catalog_id(descriptors: &[BrowserDescriptor], catalog_id: &str) -> Option<String> {
+    let (base, profile) = catalog_id
+        .split_once("__")
+        .unwrap_or((catalog_id, "default"));
+    let descriptor = find_descriptor(descriptors, base)?;
+    Some(format!("{}__{}", descriptor.id, profile))
+}
+
+/// Reads the version of the browser started by `executable` from the files installed with it.
+///
+/// Firefox and its forks ship an `application.ini`, macOS bundles an `Info.plist`, and
+/// Chromium on Windows keeps its files in a directory named after the version.
+pub fn detect_version(executable: &Path) -> Option<String> {
+    let executable = fs::canonicalize(executable).unwrap_or_else(|_| executable.to_path_buf());
+    let dir = executable.parent()?;
+    let resources = dir.parent().map(|contents| contents.join("Resources"));
+
+    [Some(dir.to_path_buf()), resources]
+        .into_iter()
+        .flatten()
+        .find_map(|dir| application_ini_version(&dir))
+        .or_else(|| {
+            bundle_plist(&executable)
+                .and_then(|plist| plist_string(&plist, "CFBundleShortVersionString"))
+        })
+        .or_else(|| version_directory(dir))
+}
+
+fn application_ini_version(dir: &Path) -> Option<String> {
+    let contents = fs::read_to_string(dir.join("application.ini")).ok()?;
+    let mut in_app = false;
+    for line in contents.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_app = section == "App";
+            continue;
+        }
+        if let Some(version) = line.strip_prefix("Version=").filter(|_| in_app) {
+            return Some(version.trim().to_string()).filter(|v| !v.is_empty());
+        }
+    }
+    None
+}
+
+/// The highest dotted version number among the directory names in `dir`.
+fn version_directory(dir: &Path) -> Option<String> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter_map(|name| {
+            let parts = name
+                .split('.')
+                .map(|part| part.parse::<u32>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            (parts.len() >= 2).then_some((parts, name))
+        })
+        .max()
+        .map(|(_, name)| name)
+}
+
+/// The `.app` bundle an executable belongs to.
+pub fn app_bundle(executable: &Path) -> Option<&Path> {
+    executable
+        .ancestors()
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+}
+
+fn bundle_plist(executable: &Path) -> Option<String> {
+    let bundle = app_bundle(executable)?;
+    fs::read_to_string(bundle.join("Contents/Info.plist")).ok()
+}
+
+/// Reads the string following `<key>{key}</key>` in an XML property list.
+pub fn plist_string(plist: &str, key: &str) -> Option<String> {
+    let marker = format!("<key>{key}</key>");
+    let after_key = &plist[plist.find(&marker)? + marker.len()..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = after_key[start..].find("</string>")? + start;
+    Some(after_key[start..end].trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// The icon file named in the `Info.plist` of the bundle an executable belongs to.
+pub fn bundle_icon(executable: &Path) -> Option<PathBuf> {
+    let icon = plist_string(&bundle_plist(executable)?, "CFBundleIconFile")?;
+    let resources = app_bundle(executable)?.join("Contents/Resources");
+    let path = resources.join(&icon);
+    if path.is_file() {
+        return Some(path);
+    }
+    Some(path.with_extension("icns")).filter(|path| path.is_file())
+}
+
+/// Sizes tried for themed icons, largest first.
+const ICON_SIZES: [&str; 7] = [
+    "512x512", "256x256", "192x192", "128x128", "96x96", "64x64", "48x48",
+];
+
+/// Looks an XDG icon name up in the hicolor theme and `pixmaps` of each data directory.
+///
+/// Absolute paths, which desktop entries may use instead of a name, are returned as they are.
+pub fn find_icon(icon: &str, data_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    data_dirs.iter().find_map(|data_dir| {
+        let hicolor = data_dir.join("icons/hicolor");
+        let scalable = hicolor.join("scalable/apps").join(format!("{icon}.svg"));
+        let sized = ICON_SIZES
+            .iter()
+            .map(|size| hicolor.join(size).join("apps").join(format!("{icon}.png")));
+        let pixmaps = ["png", "svg", "xpm"]
+            .iter()
+            .map(|ext| data_dir.join("pixmaps").join(format!("{icon}.{ext}")));
+        std::iter::once(scalable)
+            .chain(sized)
+            .chain(pixmaps)
+            .find(|candidate| candidate.is_file())
+    })
+}
@@ -1,11 +1,25 @@
+use crate::browser_descriptor::{
+    detect_version, find_descriptor, slugify, BrowserDescriptor, BrowserFamily, ReleaseChannel,
+};
+#[cfg(target_os = "linux")]
+use crate::browser_descriptor::{find_icon, stable_id};
+#[cfg(target_os = "linux")]
+use crate::desktop_entries::{self, DesktopBrowser};
+#[cfg(target_os = "linux")]
+use crate::routing::normalize_browser_key;
+#[cfg(target_os = "linux")]
+use crate::sandbox::{self, ProfileStore, SandboxedBrowser};
 use crowser::browser;
 use dirs::{config_dir, data_local_dir, home_dir};
 use serde::Serialize;
 use serde_json::Value;
+use std::path::{Path, PathBuf};
 use std::{
     fs,
     io::{self, Read},
 };
+use tauri::async_runtime::{self, RwLock};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_os::OsType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,11 +37,195 @@ pub struct ProfileDescriptor {
     pub directory: String,
 }
 
-pub fn get_browsers() -> Vec<String> {
-    let browser_vector = browser::get_all_existing_browsers();
-    let browser_names: Vec<String> = browser_vector.iter().map(|s| s.name.to_owned()).collect();
+/// Installed browsers as last detected, with the desktop entries they were matched against.
+#[derive(Debug, Clone, Default)]
+pub struct BrowserCatalog {
+    pub descriptors: Vec<BrowserDescriptor>,
+    /// Desktop entries carry the command line each browser expects to be started with.
+    #[cfg(target_os = "linux")]
+    pub desktop_browsers: Vec<DesktopBrowser>,
+}
+
+impl BrowserCatalog {
+    /// Scans the system for every installed browser with its stable id, engine, executable,
+    /// version and icon.
+    ///
+    /// This walks the disk, so the result is kept in [`BrowserCatalogState`] and only
+    /// detected again when the browser list is requested.
+    pub fn detect() -> Self {
+        #[cfg(target_os = "linux")]
+        let desktop_browsers = desktop_entries::discover();
+        #[cfg(target_os = "linux")]
+        let data_dirs = desktop_entries::data_dirs();
+
+        let mut descriptors = Vec::new();
+        for installed in browser::get_all_existing_browsers() {
+            let name = installed.name;
+            let executable = browser::get_browser_path(&installed);
+
+            #[cfg(target_os = "linux")]
+            let icon = find_desktop_browser(&desktop_browsers, name, executable.as_deref())
+                .and_then(|desktop| desktop.icon.as_deref())
+                .and_then(|icon| find_icon(icon, &data_dirs));
+            #[cfg(not(target_os = "linux"))]
+            let icon = executable.as_deref().and_then(executable_icon);
+
+            descriptors.push(BrowserDescriptor {
+                id: slugify(name),
+                display_name: name.to_string(),
+                family: BrowserFamily::from_name(name),
+                version: executable.as_deref().and_then(detect_version),
+                channel: ReleaseChannel::from_name(name),
+                icon,
+                executable,
+            });
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            add_desktop_browsers(&mut descriptors, &desktop_browsers, &data_dirs);
+            descriptors.extend(sandbox::installed().iter().map(describe_sandboxed));
+        }
+
+        Self {
+            descriptors,
+            #[cfg(target_os = "linux")]
+            desktop_browsers,
+        }
+    }
+}
+
+/// The installed browsers, detected at startup so routing a link never scans the disk.
+pub struct BrowserCatalogState {
+    inner: RwLock<BrowserCatalog>,
+}
+
+impl BrowserCatalogState {
+    pub fn load() -> Self {
+        Self {
+            inner: RwLock::new(BrowserCatalog::detect()),
+        }
+    }
+
+    pub async fn catalog(&self) -> BrowserCatalog {
+        let guard = self.inner.read().await;
+        guard.clone()
+    }
+
+    /// Detects the installed browsers again, picking up ones installed or removed since.
+    pub async fn refresh(&self) -> Vec<BrowserDescriptor> {
+        let Ok(catalog) = async_runtime::spawn_blocking(BrowserCatalog::detect).await else {
+            return self.catalog().await.descriptors;
+        };
+        let descriptors = catalog.descriptors.clone();
+        *self.inner.write().await = catalog;
+        descriptors
+    }
+}
+
+/// The cached browser catalog, or a fresh scan when the state is not managed yet.
+pub async fn browser_catalog(app: &AppHandle) -> BrowserCatalog {
+    match app.try_state::<BrowserCatalogState>() {
+        Some(state) => state.catalog().await,
+        None => BrowserCatalog::detect(),
+    }
+}
 
-    return browser_names;
+/// Icons are embedded in Windows executables and named by the bundle on macOS.
+#[cfg(not(target_os = "linux"))]
+fn executable_icon(executable: &Path) -> Option<PathBuf> {
+    #[cfg(target_os = "macos")]
+    return crate::browser_descriptor::bundle_icon(executable);
+    #[cfg(not(target_os = "macos"))]
+    Some(executable.to_path_buf())
+}
+
+/// Adds browsers found in desktop entries that are not already listed by name or executable.
+#[cfg(target_os = "linux")]
+fn add_desktop_browsers(
+    descriptors: &mut Vec<BrowserDescriptor>,
+    desktop_browsers: &[DesktopBrowser],
+    data_dirs: &[PathBuf],
+) {
+    let installed = descriptors
+        .iter()
+        .filter_map(|descriptor| descriptor.executable.as_ref())
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect::<Vec<_>>();
+
+    // Flatpak and Snap entries are listed from the sandbox with their own names.
+    let desktop_browsers = desktop_browsers
+        .iter()
+        .filter(|desktop| !sandbox::is_sandboxed_exec(&desktop.exec));
+    for desktop in desktop_browsers {
+        let key = normalize_browser_key(&desktop.name);
+        let known_name = descriptors
+            .iter()
+            .any(|descriptor| normalize_browser_key(&descriptor.display_name) == key);
+        let executable = desktop_executable(desktop);
+        let known_executable = executable
+            .as_ref()
+            .is_some_and(|path| installed.contains(path));
+        if known_name || known_executable {
+            continue;
+        }
+
+        // Desktop file ids survive renames; `$BROWSER` commands only have their program.
+        let id = match desktop.id.strip_suffix(".desktop") {
+            Some(id) => stable_id(&["desktop", id]),
+            None => stable_id(&["env", &desktop.name]),
+        };
+        let names = format!("{} {}", desktop.name, desktop.program().unwrap_or_default());
+        descriptors.push(BrowserDescriptor {
+            id,
+            display_name: desktop.name.clone(),
+            family: BrowserFamily::from_name(&names),
+            version: executable.as_deref().and_then(detect_version),
+            channel: ReleaseChannel::from_name(&names),
+            icon: desktop
+                .icon
+                .as_deref()
+                .and_then(|icon| find_icon(icon, data_dirs)),
+            executable,
+        });
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn describe_sandboxed(browser: &SandboxedBrowser) -> BrowserDescriptor {
+    let flatpak_roots = sandbox::flatpak_roots();
+    let snap_root = Path::new(sandbox::SNAP_ROOT);
+    let display_name = browser.display_name();
+    BrowserDescriptor {
+        id: browser.id(),
+        family: browser.family(),
+        executable: Some(browser.launch_command().0),
+        version: browser.version_in(&flatpak_roots, snap_root),
+        channel: ReleaseChannel::from_name(&display_name),
+        icon: browser.icon_in(&flatpak_roots, snap_root),
+        display_name,
+    }
+}
+
+/// The desktop entry for a browser, matched by name or by the executable it starts.
+#[cfg(target_os = "linux")]
+pub fn find_desktop_browser<'a>(
+    desktop_browsers: &'a [DesktopBrowser],
+    name: &str,
+    executable: Option<&Path>,
+) -> Option<&'a DesktopBrowser> {
+    let key = normalize_browser_key(name);
+    let executable = executable.and_then(|path| fs::canonicalize(path).ok());
+    desktop_browsers.iter().find(|browser| {
+        normalize_browser_key(&browser.name) == key
+            || executable.is_some() && desktop_executable(browser) == executable
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_executable(browser: &DesktopBrowser) -> Option<PathBuf> {
+    let program = desktop_entries::resolve_program(browser.program()?)?;
+    fs::canonicalize(program).ok()
 }
 
 pub fn parse_browser_kind<S: AsRef<str>>(value: S) -> Option<Browsers> {
@@ -43,6 +241,90 @@ pub fn parse_browser_kind<S: AsRef<str>>(value: S) -> Option<Browsers> {
     }
 }
 
+/// Builds the catalog id the frontend assigns to a browser/profile pair (`<browser id>__<profile>`).
+pub fn browser_catalog_id(browser_id: &str, directory: Option<&str>) -> String {
+    let suffix = directory
+        .map(slugify)
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "default".to_string());
+    format!("{}__{}", slugify(browser_id), suffix)
+}
+
+/// Resolves a catalog id back to an installed browser name and, when present, its profile.
+pub fn resolve_browser_id(
+    descriptors: &[BrowserDescriptor],
+    id: &str,
+) -> Option<(String, Option<ProfileDescriptor>)> {
+    let (base, suffix) = id.split_once("__").unwrap_or((id, "default"));
+    let name = find_descriptor(descriptors, base)?.display_name.clone();
+
+    let profile = get_profiles_for_browser(descriptors, &name)
+        .into_iter()
+        .find(|profile| slugify(&profile.directory) == suffix);
+
+    match profile {
+        Some(profile) => Some((name, Some(profile))),
+        None if suffix == "default" => Some((name, None)),
+        None => None,
+    }
+}
+
+/// Display name of the installed browser an id refers to.
+pub fn browser_name_for_id(descriptors: &[BrowserDescriptor], id: &str) -> Option<String> {
+    find_descriptor(descriptors, id).map(|descriptor| descriptor.display_name.clone())
+}
+
+/// Stable id of the installed browser shown under `name`.
+pub fn browser_id_for_name(descriptors: &[BrowserDescriptor], name: &str) -> Option<String> {
+    descriptors
+        .iter()
+        .find(|descriptor| descriptor.display_name.eq_ignore_ascii_case(name.trim()))
+        .map(|descriptor| descriptor.id.clone())
+}
+
+/// Profiles of an installed browser by display name, including sandboxed installs.
+pub fn get_profiles_for_browser(
+    browsers: &[BrowserDescriptor],
+    name: &str,
+) -> Vec<ProfileDescriptor> {
+    #[cfg(target_os = "linux")]
+    if let Some(browser) = sandbox::find_listed(browsers, name) {
+        return get_sandboxed_profiles(&browser).unwrap_or_default();
+    }
+
+    parse_browser_kind(name)
+        .and_then(|kind| get_profiles_for_kind(kind).ok())
+        .unwrap_or_default()
+}
+
+/// Reads the profiles a Flatpak or Snap browser keeps inside its sandbox.
+#[cfg(target_os = "linux")]
+pub fn get_sandboxed_profiles(
+    browser: &SandboxedBrowser,
+) -> Result<Vec<ProfileDescriptor>, Box<dyn std::error::Error>> {
+    let Some(home) = home_dir() else {
+        return Ok(Vec::new());
+    };
+    let path = browser.profile_path(&home);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    match browser.profiles {
+        ProfileStore::Chromium(_) => read_chrome_profiles(&path),
+        ProfileStore::Gecko(_) => read_firefox_profiles(&path),
+    }
+}
+
+pub fn get_profiles_for_kind(
+    kind: Browsers,
+) -> Result<Vec<ProfileDescriptor>, Box<dyn std::error::Error>> {
+    match kind {
+        Browsers::Chrome | Browsers::Edge | Browsers::Brave => get_chrome_profiles(kind),
+        Browsers::FireFox => get_firefox_profiles(),
+        Browsers::Safari => Ok(Vec::new()),
+    }
+}
+
 pub fn get_chrome_based_profiles(
     os_paths: [&str; 3],
 ) -> Result<Vec<ProfileDescriptor>, Box<dyn std::error::Error>> {
@@ -63,88 +345,95 @@ pub fn get_chrome_based_profiles(
         path.push(suffix);
 
         if path.exists() {
-            let mut file = fs::File::open(&path)?;
-            let mut contents = String::new();
-            file.read_to_string(&mut contents)?;
-
-            let json_value: Value = serde_json::from_str(&contents)?;
-
-            let info_cache = json_value
-                .get("profile")
-                .and_then(|p| p.get("info_cache"))
-                .and_then(|ic| ic.as_object())
-                .ok_or_else(|| {
-                    Box::new(io::Error::new(
-                        io::ErrorKind::InvalidData,
-                        "Could not find 'profile' or 'info_cache' in JSON.",
-                    )) as Box<dyn std::error::Error>
-                })?;
-
-            let mut profiles: Vec<ProfileDescriptor> = Vec::new();
-
-            for (profile_key, profile_data) in info_cache.iter() {
-                let directory = profile_data
-                    .get("profile_dir")
+            return read_chrome_profiles(&path);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Reads the profiles listed in a Chromium `Local State` file.
+pub fn read_chrome_profiles(
+    path: &Path,
+) -> Result<Vec<ProfileDescriptor>, Box<dyn std::error::Error>> {
+    let mut file = fs::File::open(path)?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+
+    let json_value: Value = serde_json::from_str(&contents)?;
+
+    let info_cache = json_value
+        .get("profile")
+        .and_then(|p| p.get("info_cache"))
+        .and_then(|ic| ic.as_object())
+        .ok_or_else(|| {
+            Box::new(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Could not find 'profile' or 'info_cache' in JSON.",
+            )) as Box<dyn std::error::Error>
+        })?;
+
+    let mut profiles: Vec<ProfileDescriptor> = Vec::new();
+
+    for (profile_key, profile_data) in info_cache.iter() {
+        let directory = profile_data
+            .get("profile_dir")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_owned())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| profile_key.to_owned());
+
+        let display = profile_data
+            .get("gaia_name")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_owned())
+            .or_else(|| {
+                profile_data
+                    .get("brave_sync_profile_name")
                     .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
                     .map(|s| s.to_owned())
+            })
+            .or_else(|| {
+                profile_data
+                    .get("supervised_user_name")
+                    .and_then(|v| v.as_str())
                     .filter(|s| !s.is_empty())
-                    .unwrap_or_else(|| profile_key.to_owned());
-
-                let display = profile_data
-                    .get("gaia_name")
+                    .map(|s| s.to_owned())
+            })
+            .or_else(|| {
+                profile_data
+                    .get("name")
                     .and_then(|v| v.as_str())
                     .filter(|s| !s.is_empty())
                     .map(|s| s.to_owned())
-                    .or_else(|| {
-                        profile_data
-                            .get("brave_sync_profile_name")
-                            .and_then(|v| v.as_str())
-                            .filter(|s| !s.is_empty())
-                            .map(|s| s.to_owned())
-                    })
-                    .or_else(|| {
-                        profile_data
-                            .get("supervised_user_name")
-                            .and_then(|v| v.as_str())
-                            .filter(|s| !s.is_empty())
-                            .map(|s| s.to_owned())
-                    })
-                    .or_else(|| {
-                        profile_data
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .filter(|s| !s.is_empty())
-                            .map(|s| s.to_owned())
-                    })
-                    .unwrap_or_else(|| {
-                        if profile_key.eq_ignore_ascii_case("default") {
-                            "Default".to_string()
-                        } else {
-                            directory.clone()
-                        }
-                    });
-
-                if !profiles.iter().any(|p| p.directory == directory) {
-                    profiles.push(ProfileDescriptor {
-                        display_name: display,
-                        directory,
-                    });
+            })
+            .unwrap_or_else(|| {
+                if profile_key.eq_ignore_ascii_case("default") {
+                    "Default".to_string()
+                } else {
+                    directory.clone()
                 }
-            }
-
-            if !profiles.iter().any(|p| p.directory == "Default") {
-                profiles.push(ProfileDescriptor {
-                    display_name: "Default".to_string(),
-                    directory: "Default".to_string(),
-                });
-            }
+            });
 
-            profiles.sort_by(|a, b| a.display_name.cmp(&b.display_name));
-            return Ok(profiles);
+        if !profiles.iter().any(|p| p.directory == directory) {
+            profiles.push(ProfileDescriptor {
+                display_name: display,
+                directory,
+            });
         }
     }
 
-    Ok(Vec::new())
+    if !profiles.iter().any(|p| p.directory == "Default") {
+        profiles.push(ProfileDescriptor {
+            display_name: "Default".to_string(),
+            directory: "Default".to_string(),
+        });
+    }
+
+    profiles.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    Ok(profiles)
 }
 
 pub fn get_chrome_profiles(
@@ -189,31 +478,38 @@ pub fn get_firefox_profiles() -> Result<Vec<ProfileDescriptor>, Box<dyn std::err
         }
 
         if path.exists() {
-            match fs::read_dir(path) {
-                Ok(entries) => {
-                    let mut profiles: Vec<ProfileDescriptor> = entries
-                        .filter_map(Result::ok)
-                        .filter_map(|entry| match entry.file_type() {
-                            Ok(file_type) if file_type.is_dir() => {
-                                let dir = entry.file_name().to_string_lossy().into_owned();
-                                Some(ProfileDescriptor {
-                                    display_name: dir.clone(),
-                                    directory: dir,
-                                })
-                            }
-                            _ => None,
-                        })
-                        .collect();
-                    profiles.sort_by(|a, b| a.display_name.cmp(&b.display_name));
-                    return Ok(profiles);
-                }
-                Err(e) => {
-                    eprintln!("Error reading directory: {}", e);
-                    return Ok(Vec::new());
-                }
-            }
+            return read_firefox_profiles(&path);
         }
     }
 
-    return Ok(Vec::new());
+    Ok(Vec::new())
+}
+
+/// Lists the profile folders in a Firefox profiles directory.
+pub fn read_firefox_profiles(
+    path: &Path,
+) -> Result<Vec<ProfileDescriptor>, Box<dyn std::error::Error>> {
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            let mut profiles: Vec<ProfileDescriptor> = entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| match entry.file_type() {
+                    Ok(file_type) if file_type.is_dir() => {
+                        let dir = entry.file_name().to_string_lossy().into_owned();
+                        Some(ProfileDescriptor {
+                            display_name: dir.clone(),
+                            directory: dir,
+                        })
+                    }
+                    _ => None,
+                })
+                .collect();
+            profiles.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+            Ok(profiles)
+        }
+        Err(e) => {
+            eprintln!("Error reading directory: {}", e);
+            Ok(Vec::new())
+        }
+    }
 }
@@ -0,0 +1,96 @@
+//! Stages that tidy a link up before it is handed to a browser.
+
+pub mod redirects;
+pub mod shortlinks;
+pub mod tracking;
+
+use chrono::Utc;
+use serde_json::Value;
+use std::io::ErrorKind;
+use tauri::async_runtime::{self, RwLock};
+use tauri::AppHandle;
+use tauri_plugin_store::{Error as StoreError, StoreExt};
+
+pub use redirects::unwrap_redirects;
+pub use shortlinks::{expand_short_link, ShortLinkCache, ShortLinkExpansion};
+pub use tracking::{strip_tracking_parameters, TrackingCleanup, DEFAULT_TRACKING_PARAMETERS};
+
+const SHORT_LINKS_STORE: &str = "short-links.json";
+const SHORT_LINKS_KEY: &str = "expansions";
+
+/// Short links expanded so far, cached on disk so each one is only looked up once.
+pub struct ShortLinkCacheState {
+    inner: RwLock<ShortLinkCache>,
+}
+
+impl ShortLinkCacheState {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let store = app
+            .store(SHORT_LINKS_STORE)
+            .map_err(|err| err.to_string())?;
+
+        if let Err(err) = store.reload() {
+            match err {
+                StoreError::Io(ref io_err) if io_err.kind() == ErrorKind::NotFound => {}
+                other => return Err(other.to_string()),
+            }
+        }
+
+        let cache = match store.get(SHORT_LINKS_KEY) {
+            Some(data) => serde_json::from_value(data).map_err(|err| err.to_string())?,
+            None => ShortLinkCache::default(),
+        };
+        Ok(Self {
+            inner: RwLock::new(cache),
+        })
+    }
+
+    /// Expands `url` if it is a configured short link, from the cache when possible.
+    pub async fn expand(
+        &self,
+        app: &AppHandle,
+        url: &str,
+        settings: &ShortLinkExpansion,
+    ) -> Option<String> {
+        let parsed = url::Url::parse(url).ok()?;
+        if !settings.enabled || !settings.is_short_link(&parsed) {
+            return None;
+        }
+        if let Some(target) = self.inner.read().await.get(url) {
+            return Some(target.to_string());
+        }
+
+        let (link, options) = (url.to_string(), settings.clone());
+        let target = async_runtime::spawn_blocking(move || expand_short_link(&link, &options))
+            .await
+            .ok()
+            .flatten()?;
+
+        {
+            let mut guard = self.inner.write().await;
+            guard.insert(url, &target, &Utc::now().to_rfc3339());
+        }
+        if let Err(err) = self.persist(app).await {
+            eprintln!("failed to save short link cache: {err}");
+        }
+        Some(target)
+    }
+
+    /// The cached expansion of `url`, without contacting the shortener.
+    pub async fn cached(&self, url: &str) -> Option<String> {
+        self.inner.read().await.get(url).map(str::to_string)
+    }
+
+    async fn persist(&self, app: &AppHandle) -> Result<(), String> {
+        let value: Value = {
+            let guard = self.inner.read().await;
+            serde_json::to_value(&*guard).map_err(|err| err.to_string())?
+        };
+
+        let store = app
+            .store(SHORT_LINKS_STORE)
+            .map_err(|err| err.to_string())?;
+        store.set(SHORT_LINKS_KEY.to_string(), value);
+        store.save().map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,122 @@
+use url::Url;
+
+/// Wrappers nested deeper than this are left as they are.
+const MAX_UNWRAP_DEPTH: usize = 5;
+
+/// A link-wrapping service that carries the real destination in a query parameter.
+struct RedirectWrapper {
+    /// Hosts serving the wrapper; a leading `*.` also matches the domain's subdomains.
+    hosts: &'static [&'static str],
+    /// Path the redirect is served from, when the host also serves other pages.
+    path: Option<&'static str>,
+    /// Parameters holding the destination, tried in order.
+    params: &'static [&'static str],
+}
+
+const WRAPPERS: [RedirectWrapper; 10] = [
+    // Outlook / Microsoft Defender SafeLinks.
+    RedirectWrapper {
+        hosts: &["*.safelinks.protection.outlook.com"],
+        path: None,
+        params: &["url"],
+    },
+    // Google search results and Gmail.
+    RedirectWrapper {
+        hosts: &["google.*"],
+        path: Some("/url"),
+        params: &["q", "url"],
+    },
+    RedirectWrapper {
+        hosts: &["slack-redir.net"],
+        path: Some("/link"),
+        params: &["url"],
+    },
+    RedirectWrapper {
+        hosts: &["l.facebook.com", "lm.facebook.com", "l.messenger.com"],
+        path: Some("/l.php"),
+        params: &["u"],
+    },
+    RedirectWrapper {
+        hosts: &["l.instagram.com"],
+        path: None,
+        params: &["u"],
+    },
+    RedirectWrapper {
+        hosts: &["*.linkedin.com"],
+        path: Some("/redir/redirect"),
+        params: &["url"],
+    },
+    RedirectWrapper {
+        hosts: &["*.linkedin.com"],
+        path: Some("/safety/go"),
+        params: &["url"],
+    },
+    RedirectWrapper {
+        hosts: &["*.youtube.com"],
+        path: Some("/redirect"),
+        params: &["q"],
+    },
+    RedirectWrapper {
+        hosts: &["steamcommunity.com"],
+        path: Some("/linkfilter/"),
+        params: &["url", "u"],
+    },
+    RedirectWrapper {
+        hosts: &["out.reddit.com"],
+        path: None,
+        params: &["url"],
+    },
+];
+
+impl RedirectWrapper {
+    fn serves(&self, url: &Url) -> bool {
+        let host = url.host_str().unwrap_or_default().trim_end_matches('.');
+        let host = host.to_ascii_lowercase();
+        let host_matches = self.hosts.iter().any(|pattern| {
+            if let Some(domain) = pattern.strip_prefix("*.") {
+                host == domain || host.ends_with(&format!(".{domain}"))
+            } else if let Some(name) = pattern.strip_suffix(".*") {
+                // Country variants such as google.de or www.google.co.uk.
+                let host = host.strip_prefix("www.").unwrap_or(&host);
+                host.strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('.') && rest.len() > 1)
+            } else {
+                host == *pattern
+            }
+        });
+        host_matches && self.path.is_none_or(|path| url.path() == path)
+    }
+
+    fn destination(&self, url: &Url) -> Option<Url> {
+        self.params.iter().find_map(|param| {
+            let value = url
+                .query_pairs()
+                .find(|(name, _)| name == param)
+                .map(|(_, value)| value.into_owned())?;
+            Url::parse(value.trim())
+                .ok()
+                .filter(|target| matches!(target.scheme(), "http" | "https"))
+        })
+    }
+}
+
+/// Extracts the real destination from known redirect wrappers, following nested ones.
+///
+/// Works purely on the URL, without network access; returns `None` when `url` is not a
+/// wrapper or its destination is missing or not an http(s) link.
+pub fn unwrap_redirects(url: &str) -> Option<String> {
+    let mut current = Url::parse(url).ok()?;
+    let mut unwrapped = false;
+    for _ in 0..MAX_UNWRAP_DEPTH {
+        let Some(target) = WRAPPERS
+            .iter()
+            .find(|wrapper| wrapper.serves(&current))
+            .and_then(|wrapper| wrapper.destination(&current))
+        else {
+            break;
+        };
+        current = target;
+        unwrapped = true;
+    }
+    unwrapped.then(|| current.to_string())
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Shorteners expanded out of the box.
+pub const DEFAULT_SHORTENERS: [&str; 16] = [
+    "bit.ly",
+    "t.co",
+    "lnkd.in",
+    "tinyurl.com",
+    "ow.ly",
+    "buff.ly",
+    "is.gd",
+    "rebrand.ly",
+    "t.ly",
+    "cutt.ly",
+    "tiny.cc",
+    "rb.gy",
+    "shorturl.at",
+    "amzn.to",
+    "trib.al",
+    "dlvr.it",
+];
+
+/// Shortener hops followed for a single link before giving up.
+const MAX_HOPS: usize = 5;
+/// Expansions kept in the on-disk cache; the oldest are dropped first.
+const MAX_CACHED_LINKS: usize = 1000;
+
+/// Whether and how short links are expanded before rules are evaluated.
+///
+/// Off by default: expanding a link contacts the shortener, which then sees the click.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ShortLinkExpansion {
+    pub enabled: bool,
+    /// Shortener hosts, matched exactly and ignoring case.
+    pub shorteners: Vec<String>,
+    /// Time allowed for the whole expansion, in milliseconds.
+    pub timeout_ms: u64,
+}
+
+impl Default for ShortLinkExpansion {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            shorteners: DEFAULT_SHORTENERS.map(str::to_string).to_vec(),
+            timeout_ms: 1500,
+        }
+    }
+}
+
+impl ShortLinkExpansion {
+    /// Lowercases and de-duplicates shortener hosts and keeps the timeout within 100ms..10s.
+    pub fn normalized(mut self) -> Self {
+        let mut hosts: Vec<String> = Vec::new();
+        for host in self.shorteners {
+            let host = host.trim();
+            let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+            let host = host.trim_end_matches('/').to_lowercase();
+            if !host.is_empty() && !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        self.shorteners = hosts;
+        self.timeout_ms = self.timeout_ms.clamp(100, 10_000);
+        self
+    }
+
+    pub fn is_short_link(&self, url: &Url) -> bool {
+        let host = url
+            .host_str()
+            .unwrap_or_default()
+            .trim_end_matches('.')
+            .to_lowercase();
+        matches!(url.scheme(), "http" | "https") && self.shorteners.contains(&host)
+    }
+}
+
+/// A previously expanded short link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedExpansion {
+    pub target: String,
+    pub resolved_at: String,
+}
+
+/// Expansions keyed by short link, as persisted in `short-links.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ShortLinkCache {
+    links: BTreeMap<String, CachedExpansion>,
+}
+
+impl ShortLinkCache {
+    pub fn get(&self, url: &str) -> Option<&str> {
+        self.links.get(url).map(|entry| entry.target.as_str())
+    }
+
+    pub fn insert(&mut self, url: &str, target: &str, resolved_at: &str) {
+        self.links.insert(
+            url.to_string(),
+            CachedExpansion {
+                target: target.to_string(),
+                resolved_at: resolved_at.to_string(),
+            },
+        );
+        while self.links.len() > MAX_CACHED_LINKS {
+            let Some(oldest) = self
+                .links
+                .iter()
+                .min_by(|(_, a), (_, b)| a.resolved_at.cmp(&b.resolved_at))
+                .map(|(url, _)| url.clone())
+            else {
+                break;
+            };
+            self.links.remove(&oldest);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+}
+
+/// Follows the redirects of known shorteners with `HEAD` requests, never downloading a body.
+///
+/// Blocks for at most the configured timeout. Fails open: any error, timeout or non-redirect
+/// response yields `None` so the original link is routed unchanged. Only hops through
+/// configured shortener hosts are followed; the first other host is the destination.
+pub fn expand_short_link(url: &str, settings: &ShortLinkExpansion) -> Option<String> {
+    let mut current = Url::parse(url).ok()?;
+    if !settings.enabled || !settings.is_short_link(&current) {
+        return None;
+    }
+
+    let deadline = Instant::now() + Duration::from_millis(settings.timeout_ms);
+    let agent = ureq::AgentBuilder::new()
+        .redirects(0)
+        .user_agent(concat!("open-with-browser/", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    for _ in 0..MAX_HOPS {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        current = redirect_location(&agent, &current, remaining)?;
+        if !settings.is_short_link(&current) {
+            return Some(current.to_string());
+        }
+    }
+    None
+}
+
+fn redirect_location(agent: &ureq::Agent, url: &Url, timeout: Duration) -> Option<Url> {
+    let response = match agent.head(url.as_str()).timeout(timeout).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(_, response)) => response,
+        Err(ureq::Error::Transport(_)) => return None,
+    };
+    if !(300..400).contains(&response.status()) {
+        return None;
+    }
+    let target = url.join(response.header("location")?).ok()?;
+    matches!(target.scheme(), "http" | "https").then_some(target)
+}
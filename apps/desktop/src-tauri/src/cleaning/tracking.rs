@@ -0,0 +1,138 @@
+use crate::rules::matching::wildcard_matches;
+use serde::{Deserialize, Serialize};
+use url::{form_urlencoded, Url};
+
+/// Query parameters stripped out of the box; a trailing `*` matches any suffix.
+pub const DEFAULT_TRACKING_PARAMETERS: [&str; 24] = [
+    "utm_*",
+    "fbclid",
+    "gclid",
+    "gclsrc",
+    "dclid",
+    "gbraid",
+    "wbraid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "ttclid",
+    "li_fat_id",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "__hssc",
+    "__hstc",
+    "__hsfp",
+    "mkt_tok",
+    "oly_anon_id",
+    "oly_enc_id",
+    "vero_id",
+];
+
+/// Which query parameters are removed from links before launch, and where they are left alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TrackingCleanup {
+    pub enabled: bool,
+    /// Parameter names, matched ignoring case; `*` is a wildcard.
+    pub parameters: Vec<String>,
+    /// Hosts whose links keep every parameter, including their subdomains; `*` is a wildcard.
+    pub exceptions: Vec<String>,
+}
+
+impl Default for TrackingCleanup {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            parameters: DEFAULT_TRACKING_PARAMETERS.map(str::to_string).to_vec(),
+            exceptions: Vec::new(),
+        }
+    }
+}
+
+impl TrackingCleanup {
+    /// Lowercases and de-duplicates parameters and exceptions, dropping blank entries.
+    pub fn normalized(mut self) -> Self {
+        self.parameters = normalize_list(self.parameters);
+        self.exceptions = normalize_list(
+            self.exceptions
+                .into_iter()
+                .map(|host| {
+                    let host = host.trim();
+                    let host = host.split_once("://").map_or(host, |(_, rest)| rest);
+                    host.trim_end_matches('/').to_string()
+                })
+                .collect(),
+        );
+        self
+    }
+
+    fn is_tracking(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.parameters
+            .iter()
+            .any(|pattern| wildcard_matches(pattern, &name))
+    }
+
+    fn is_exempt(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_lowercase();
+        self.exceptions.iter().any(|pattern| {
+            if pattern.contains('*') {
+                wildcard_matches(pattern, &host)
+            } else {
+                host == *pattern || host.ends_with(&format!(".{pattern}"))
+            }
+        })
+    }
+}
+
+fn normalize_list(values: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim().to_lowercase();
+        if !value.is_empty() && !normalized.contains(&value) {
+            normalized.push(value);
+        }
+    }
+    normalized
+}
+
+/// Removes tracking parameters from `url`, returning the cleaned URL and the names removed.
+///
+/// Returns `None` when nothing was removed, so callers can keep the link exactly as it came.
+pub fn strip_tracking_parameters(
+    url: &str,
+    cleanup: &TrackingCleanup,
+) -> Option<(String, Vec<String>)> {
+    if !cleanup.enabled {
+        return None;
+    }
+    let mut parsed = Url::parse(url).ok()?;
+    if cleanup.is_exempt(parsed.host_str().unwrap_or_default()) {
+        return None;
+    }
+    let query = parsed.query()?.to_string();
+
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    // Pairs are filtered in their raw form so the parameters that stay keep their encoding.
+    for pair in query.split('&') {
+        let name = form_urlencoded::parse(pair.as_bytes())
+            .next()
+            .map(|(name, _)| name.into_owned())
+            .unwrap_or_default();
+        if !name.is_empty() && cleanup.is_tracking(&name) {
+            removed.push(name);
+        } else {
+            kept.push(pair);
+        }
+    }
+    if removed.is_empty() {
+        return None;
+    }
+
+    let kept = kept.join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    Some((parsed.to_string(), removed))
+}
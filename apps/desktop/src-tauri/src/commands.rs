@@ -1,14 +1,23 @@
 use crate::{
+    browser_descriptor::BrowserDescriptor,
     browser_details::{
-        get_browsers, get_chrome_profiles, get_firefox_profiles, parse_browser_kind, Browsers,
-        ProfileDescriptor,
+        browser_catalog, browser_id_for_name, get_profiles_for_kind, parse_browser_kind,
+        BrowserCatalogState, ProfileDescriptor,
     },
+    cleaning::{ShortLinkExpansion, TrackingCleanup},
+    configuration::{self, ConfigFormat, FinickyImportReport, ImportMode, ImportReport},
     diagnostics::{DiagnosticEntry, DiagnosticsState},
     platform,
-    preferences::{FallbackPreference, PreferencesState, ProfilePreference},
+    preferences::{FallbackPreference, Preferences, PreferencesState, ProfilePreference},
     routing::{
-        simulate_link_payload, IncomingLink, LaunchDecision, RoutingSnapshot, RoutingStateHandle,
+        simulate_link_payload, IncomingLink, LaunchDecision, RouteExplanation, RoutingSnapshot,
+        RoutingStateHandle,
     },
+    rules::{
+        RuleInput, RuleKind, RuleLint, RuleStatsEntry, RuleStatsState, RulesSnapshot, RulesState,
+    },
+    schemes::{self, SchemeHandler},
+    workspaces,
 };
 use serde::{Deserialize, Serialize};
 use std::process::Command;
@@ -74,28 +83,55 @@ fn current_http_handler_linux() -> Result<String, String> {
     Ok(value)
 }
 
+/// Display names of the installed browsers as last detected.
+#[tauri::command]
+pub async fn get_available_browsers(app_handle: AppHandle) -> Vec<String> {
+    browser_catalog(&app_handle)
+        .await
+        .descriptors
+        .into_iter()
+        .map(|descriptor| descriptor.display_name)
+        .collect()
+}
+
+/// Detects the installed browsers again and lists them.
 #[tauri::command]
-pub fn get_available_browsers() -> Vec<String> {
-    get_browsers()
+pub async fn list_browsers(app_handle: AppHandle) -> Result<Vec<BrowserDescriptor>, String> {
+    let state = app_handle
+        .try_state::<BrowserCatalogState>()
+        .ok_or_else(|| "Browser catalog not initialised".to_string())?;
+    Ok(state.refresh().await)
 }
 
 #[tauri::command]
-pub fn get_profiles(browser_kind: String) -> Result<Vec<ProfileDescriptor>, String> {
+pub async fn get_profiles(
+    app_handle: AppHandle,
+    browser_kind: String,
+) -> Result<Vec<ProfileDescriptor>, String> {
+    #[cfg(target_os = "linux")]
+    if let Some(browser) = crate::sandbox::find_listed(
+        &browser_catalog(&app_handle).await.descriptors,
+        &browser_kind,
+    ) {
+        return crate::browser_details::get_sandboxed_profiles(&browser).map_err(map_error);
+    }
+
     let kind = parse_browser_kind(browser_kind.as_str())
         .ok_or_else(|| format!("Unsupported browser: {browser_kind}"))?;
 
-    match kind {
-        Browsers::Chrome | Browsers::Edge | Browsers::Brave => {
-            get_chrome_profiles(kind).map_err(map_error)
-        }
-        Browsers::FireFox => get_firefox_profiles().map_err(map_error),
-        Browsers::Safari => Ok(Vec::new()),
-    }
+    get_profiles_for_kind(kind).map_err(map_error)
 }
 
 #[tauri::command]
-pub async fn routing_snapshot(state: RoutingStateHandle<'_>) -> Result<RoutingSnapshot, String> {
-    Ok(state.snapshot().await)
+pub async fn routing_snapshot(
+    app_handle: AppHandle,
+    state: RoutingStateHandle<'_>,
+) -> Result<RoutingSnapshot, String> {
+    let mut snapshot = state.snapshot().await;
+    if let Some(preferences) = app_handle.try_state::<PreferencesState>() {
+        snapshot.active_workspace = preferences.active_workspace().await;
+    }
+    Ok(snapshot)
 }
 
 #[tauri::command]
@@ -126,6 +162,16 @@ pub async fn simulate_incoming_link(
     state.register_incoming(&app_handle, link).await
 }
 
+#[tauri::command]
+pub async fn explain_route(
+    app_handle: AppHandle,
+    url: String,
+    source_app: Option<String>,
+    source_context: Option<String>,
+) -> Result<RouteExplanation, String> {
+    Ok(crate::routing::explain_route(&app_handle, &url, source_app, source_context).await)
+}
+
 #[tauri::command]
 pub async fn is_default_browser(app_handle: AppHandle) -> Result<bool, String> {
     #[cfg(target_os = "windows")]
@@ -200,12 +246,34 @@ pub async fn open_default_browser_settings(_app_handle: AppHandle) -> Result<(),
 
 #[tauri::command]
 pub async fn register_browser_handlers(app_handle: AppHandle) -> Result<(), String> {
-    platform::register_as_browser(&app_handle)
+    let handled = match app_handle.try_state::<PreferencesState>() {
+        Some(state) => state.handled_schemes().await,
+        None => schemes::handled_schemes(&[]),
+    };
+    platform::register_as_browser(&app_handle, &handled)
 }
 
 #[derive(Debug, Serialize)]
 pub struct PreferencesSnapshot {
     pub fallback: Option<FallbackPreference>,
+    pub workspaces: Vec<String>,
+    pub active_workspace: Option<String>,
+    pub tracking: TrackingCleanup,
+    pub short_links: ShortLinkExpansion,
+    pub schemes: Vec<SchemeHandler>,
+}
+
+impl From<Preferences> for PreferencesSnapshot {
+    fn from(preferences: Preferences) -> Self {
+        Self {
+            fallback: preferences.fallback,
+            workspaces: preferences.workspaces,
+            active_workspace: preferences.active_workspace,
+            tracking: preferences.tracking,
+            short_links: preferences.short_links,
+            schemes: preferences.schemes,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -216,12 +284,11 @@ pub struct ProfileSelectionInput {
 
 #[tauri::command]
 pub async fn get_preferences(app_handle: AppHandle) -> Result<PreferencesSnapshot, String> {
-    if let Some(state) = app_handle.try_state::<PreferencesState>() {
-        let fallback = state.fallback().await;
-        Ok(PreferencesSnapshot { fallback })
-    } else {
-        Ok(PreferencesSnapshot { fallback: None })
-    }
+    let preferences = match app_handle.try_state::<PreferencesState>() {
+        Some(state) => state.snapshot().await,
+        None => Preferences::default(),
+    };
+    Ok(preferences.into())
 }
 
 #[tauri::command]
@@ -236,10 +303,12 @@ pub async fn set_fallback_browser(
 
     match browser {
         Some(name) if !name.is_empty() => {
+            let browsers = browser_catalog(&app_handle).await.descriptors;
             state
                 .set_fallback(
                     &app_handle,
                     Some(FallbackPreference {
+                        browser_id: browser_id_for_name(&browsers, &name),
                         browser: name,
                         profile: profile.map(|p| ProfilePreference {
                             label: p.label,
@@ -253,6 +322,168 @@ pub async fn set_fallback_browser(
     }
 }
 
+#[tauri::command]
+pub async fn set_workspaces(
+    app_handle: AppHandle,
+    names: Vec<String>,
+) -> Result<PreferencesSnapshot, String> {
+    let preferences = workspaces::define_workspaces(&app_handle, names).await?;
+    Ok(preferences.into())
+}
+
+#[tauri::command]
+pub async fn set_tracking_cleanup(
+    app_handle: AppHandle,
+    cleanup: Option<TrackingCleanup>,
+) -> Result<TrackingCleanup, String> {
+    let state = app_handle
+        .try_state::<PreferencesState>()
+        .ok_or_else(|| "Preferences state not initialised".to_string())?;
+    state.set_tracking_cleanup(&app_handle, cleanup).await
+}
+
+#[tauri::command]
+pub async fn set_short_link_expansion(
+    app_handle: AppHandle,
+    settings: Option<ShortLinkExpansion>,
+) -> Result<ShortLinkExpansion, String> {
+    let state = app_handle
+        .try_state::<PreferencesState>()
+        .ok_or_else(|| "Preferences state not initialised".to_string())?;
+    state.set_short_link_expansion(&app_handle, settings).await
+}
+
+/// Replaces the extra schemes that are handled and re-registers with the OS to claim them.
+#[tauri::command]
+pub async fn set_scheme_handlers(
+    app_handle: AppHandle,
+    handlers: Vec<SchemeHandler>,
+) -> Result<Vec<SchemeHandler>, String> {
+    if !handlers.is_empty() && !platform::claims_custom_schemes() {
+        return Err("Only http and https links can be handled on this system.".to_string());
+    }
+    let state = app_handle
+        .try_state::<PreferencesState>()
+        .ok_or_else(|| "Preferences state not initialised".to_string())?;
+    let browsers = browser_catalog(&app_handle).await.descriptors;
+    let handlers = handlers
+        .into_iter()
+        .map(|mut handler| {
+            if let Some(target) = handler.target.as_mut() {
+                target.browser_id = browser_id_for_name(&browsers, &target.browser);
+            }
+            handler
+        })
+        .collect();
+    let handlers = state.set_scheme_handlers(&app_handle, handlers).await?;
+    platform::register_as_browser(&app_handle, &schemes::handled_schemes(&handlers))?;
+    Ok(handlers)
+}
+
+#[tauri::command]
+pub async fn set_active_workspace(
+    app_handle: AppHandle,
+    workspace: Option<String>,
+) -> Result<Option<String>, String> {
+    workspaces::activate_workspace(&app_handle, workspace).await
+}
+
+fn rules_state(app_handle: &AppHandle) -> Result<State<'_, RulesState>, String> {
+    app_handle
+        .try_state::<RulesState>()
+        .ok_or_else(|| "Rules state not initialised".to_string())
+}
+
+#[tauri::command]
+pub async fn list_rules(app_handle: AppHandle) -> Result<RulesSnapshot, String> {
+    Ok(rules_state(&app_handle)?.snapshot().await)
+}
+
+#[tauri::command]
+pub async fn upsert_rule(app_handle: AppHandle, rule: RuleInput) -> Result<RulesSnapshot, String> {
+    let browsers = browser_catalog(&app_handle).await.descriptors;
+    rules_state(&app_handle)?
+        .upsert(&app_handle, rule, &browsers)
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_rule(app_handle: AppHandle, id: String) -> Result<RulesSnapshot, String> {
+    let snapshot = rules_state(&app_handle)?.delete(&app_handle, &id).await?;
+    if let Some(stats) = app_handle.try_state::<RuleStatsState>() {
+        stats.forget(&app_handle, &id).await?;
+    }
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn reorder_rules(
+    app_handle: AppHandle,
+    kind: RuleKind,
+    ids: Vec<String>,
+) -> Result<RulesSnapshot, String> {
+    rules_state(&app_handle)?
+        .reorder(&app_handle, kind, &ids)
+        .await
+}
+
+#[tauri::command]
+pub async fn lint_rules(app_handle: AppHandle) -> Result<Vec<RuleLint>, String> {
+    let browsers = browser_catalog(&app_handle).await.descriptors;
+    Ok(rules_state(&app_handle)?.lint(&browsers).await)
+}
+
+#[tauri::command]
+pub async fn rule_stats(app_handle: AppHandle) -> Result<Vec<RuleStatsEntry>, String> {
+    let rules = rules_state(&app_handle)?.snapshot().await;
+    let stats = app_handle
+        .try_state::<RuleStatsState>()
+        .ok_or_else(|| "Rule stats not initialised".to_string())?;
+    Ok(stats.report(&rules).await)
+}
+
+#[tauri::command]
+pub async fn export_configuration(
+    app_handle: AppHandle,
+    format: Option<ConfigFormat>,
+) -> Result<String, String> {
+    configuration::export_configuration(&app_handle, format.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn import_configuration(
+    app_handle: AppHandle,
+    contents: String,
+    format: Option<ConfigFormat>,
+    mode: Option<ImportMode>,
+    dry_run: Option<bool>,
+) -> Result<ImportReport, String> {
+    configuration::import_configuration(
+        &app_handle,
+        &contents,
+        format,
+        mode.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn import_finicky_configuration(
+    app_handle: AppHandle,
+    contents: String,
+    mode: Option<ImportMode>,
+    dry_run: Option<bool>,
+) -> Result<FinickyImportReport, String> {
+    configuration::import_finicky_configuration(
+        &app_handle,
+        &contents,
+        mode.unwrap_or_default(),
+        dry_run.unwrap_or(false),
+    )
+    .await
+}
+
 #[tauri::command]
 pub fn get_diagnostics(state: State<DiagnosticsState>) -> Vec<DiagnosticEntry> {
     let mut entries = state.snapshot();
@@ -0,0 +1,321 @@
+use crate::preferences::{FallbackPreference, Preferences};
+use crate::rules::validation::{normalize_domain_rule, normalize_file_type_rule};
+use crate::rules::{RuleKind, RulesSnapshot};
+use crate::schemes::normalize_handlers;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Current version of the portable configuration document.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Rules and preferences in a form that can be handed to another machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDocument {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exported_at: Option<String>,
+    #[serde(default)]
+    pub preferences: Preferences,
+    #[serde(default)]
+    pub rules: RulesSnapshot,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Add new rules and overwrite ones with the same id, keeping everything else.
+    ///
+    /// Preferences combine as follows: the imported fallback wins when it names one, scheme
+    /// handlers are merged by scheme with the imported one winning, workspaces are unioned and
+    /// the local active workspace is kept. Tracking cleanup and short link settings are taken
+    /// from the document unless it leaves them at their defaults, which is also what a document
+    /// that omits them parses to.
+    #[default]
+    Merge,
+    /// Discard the current rules and preferences in favour of the imported ones.
+    Replace,
+}
+
+/// A rule that an import would add, change or remove.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleChange {
+    pub id: String,
+    pub kind: RuleKind,
+    /// Pattern or extension, whichever identifies the rule to a person.
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackChange {
+    pub from: Option<FallbackPreference>,
+    pub to: Option<FallbackPreference>,
+}
+
+/// A preference other than the fallback that an import would change.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceChange {
+    /// Preference key as it appears in the document, e.g. `shortLinks`.
+    pub name: String,
+    pub from: serde_json::Value,
+    pub to: serde_json::Value,
+}
+
+/// What importing a document would change, computed before anything is written.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub added: Vec<RuleChange>,
+    pub updated: Vec<RuleChange>,
+    pub removed: Vec<RuleChange>,
+    pub unchanged: usize,
+    pub fallback: Option<FallbackChange>,
+    pub preferences: Vec<PreferenceChange>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.updated.is_empty()
+            && self.removed.is_empty()
+            && self.fallback.is_none()
+            && self.preferences.is_empty()
+    }
+}
+
+impl ConfigDocument {
+    pub fn new(preferences: Preferences, rules: RulesSnapshot, exported_at: String) -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            exported_at: Some(exported_at),
+            preferences,
+            rules,
+        }
+    }
+
+    pub fn to_string(&self, format: ConfigFormat) -> Result<String, String> {
+        match format {
+            ConfigFormat::Json => serde_json::to_string_pretty(self).map_err(|err| err.to_string()),
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Parses a document, detecting JSON or TOML when no format is given.
+    pub fn parse(contents: &str, format: Option<ConfigFormat>) -> Result<Self, String> {
+        let format = format.unwrap_or_else(|| {
+            if contents.trim_start().starts_with('{') {
+                ConfigFormat::Json
+            } else {
+                ConfigFormat::Toml
+            }
+        });
+
+        let document = match format {
+            ConfigFormat::Json => serde_json::from_str::<ConfigDocument>(contents)
+                .map_err(|err| format!("Invalid JSON configuration: {err}"))?,
+            ConfigFormat::Toml => toml::from_str::<ConfigDocument>(contents)
+                .map_err(|err| format!("Invalid TOML configuration: {err}"))?,
+        };
+
+        if document.version == 0 || document.version > CONFIG_VERSION {
+            return Err(format!(
+                "Configuration version {} is not supported; expected {CONFIG_VERSION} or lower.",
+                document.version
+            ));
+        }
+
+        Ok(document)
+    }
+
+    /// Validates every imported rule and scheme handler, assigning ids to rules that lack one.
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.rules.domain_rules = self
+            .rules
+            .domain_rules
+            .into_iter()
+            .map(|rule| {
+                let pattern = rule.effective_pattern().to_string();
+                normalize_domain_rule(rule).map_err(|err| format!("Domain rule '{pattern}': {err}"))
+            })
+            .collect::<Result<_, _>>()?;
+        self.rules.file_type_rules = self
+            .rules
+            .file_type_rules
+            .into_iter()
+            .map(|rule| {
+                let extension = rule.extension.clone();
+                normalize_file_type_rule(rule)
+                    .map_err(|err| format!("File type rule '{extension}': {err}"))
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.preferences.schemes = normalize_handlers(self.preferences.schemes)?;
+
+        for rule in &mut self.rules.domain_rules {
+            assign_id(&mut rule.id);
+        }
+        for rule in &mut self.rules.file_type_rules {
+            assign_id(&mut rule.id);
+        }
+        Ok(self)
+    }
+}
+
+fn assign_id(id: &mut String) {
+    if id.trim().is_empty() {
+        *id = Uuid::new_v4().to_string();
+    }
+}
+
+/// Combines the current configuration with an imported one according to `mode`.
+pub fn apply_import(
+    preferences: &Preferences,
+    rules: &RulesSnapshot,
+    document: ConfigDocument,
+    mode: ImportMode,
+) -> (Preferences, RulesSnapshot) {
+    match mode {
+        ImportMode::Replace => (document.preferences, document.rules),
+        ImportMode::Merge => {
+            let mut merged = rules.clone();
+            for rule in document.rules.domain_rules {
+                match merged.domain_rules.iter_mut().find(|r| r.id == rule.id) {
+                    Some(existing) => *existing = rule,
+                    None => merged.domain_rules.push(rule),
+                }
+            }
+            for rule in document.rules.file_type_rules {
+                match merged.file_type_rules.iter_mut().find(|r| r.id == rule.id) {
+                    Some(existing) => *existing = rule,
+                    None => merged.file_type_rules.push(rule),
+                }
+            }
+
+            let imported = document.preferences;
+            let mut workspaces = preferences.workspaces.clone();
+            workspaces.extend(imported.workspaces);
+            let mut schemes = preferences.schemes.clone();
+            for handler in imported.schemes {
+                match schemes.iter_mut().find(|h| h.scheme == handler.scheme) {
+                    Some(existing) => *existing = handler,
+                    None => schemes.push(handler),
+                }
+            }
+            let mut preferences = Preferences {
+                fallback: imported.fallback.or_else(|| preferences.fallback.clone()),
+                workspaces,
+                active_workspace: preferences.active_workspace.clone(),
+                tracking: unless_default(imported.tracking, &preferences.tracking),
+                short_links: unless_default(imported.short_links, &preferences.short_links),
+                schemes,
+            };
+            preferences.normalize_workspaces();
+            (preferences, merged)
+        }
+    }
+}
+
+/// The imported value, or the current one when the document left it at its default.
+fn unless_default<T: Default + PartialEq + Clone>(imported: T, current: &T) -> T {
+    if imported == T::default() {
+        current.clone()
+    } else {
+        imported
+    }
+}
+
+/// Lists the rule and preference changes between two configurations.
+pub fn diff_configuration(
+    current_preferences: &Preferences,
+    current_rules: &RulesSnapshot,
+    next_preferences: &Preferences,
+    next_rules: &RulesSnapshot,
+) -> ConfigDiff {
+    let mut diff = ConfigDiff::default();
+
+    let current = rule_entries(current_rules);
+    let next = rule_entries(next_rules);
+    for (change, value) in &next {
+        match current
+            .iter()
+            .find(|(existing, _)| existing.id == change.id)
+        {
+            None => diff.added.push(change.clone()),
+            Some((_, existing)) if existing != value => diff.updated.push(change.clone()),
+            Some(_) => diff.unchanged += 1,
+        }
+    }
+    for (change, _) in &current {
+        if !next.iter().any(|(candidate, _)| candidate.id == change.id) {
+            diff.removed.push(change.clone());
+        }
+    }
+
+    let from = current_preferences.fallback.clone();
+    let to = next_preferences.fallback.clone();
+    if serde_json::to_value(&from).ok() != serde_json::to_value(&to).ok() {
+        diff.fallback = Some(FallbackChange { from, to });
+    }
+
+    let current = preference_entries(current_preferences);
+    let next = preference_entries(next_preferences);
+    for ((name, from), (_, to)) in current.into_iter().zip(next) {
+        if from != to {
+            diff.preferences.push(PreferenceChange {
+                name: name.to_string(),
+                from,
+                to,
+            });
+        }
+    }
+
+    diff
+}
+
+/// Preferences besides the fallback, keyed as in the document, for comparison.
+fn preference_entries(preferences: &Preferences) -> Vec<(&'static str, serde_json::Value)> {
+    vec![
+        ("tracking", serde_json::to_value(&preferences.tracking)),
+        ("shortLinks", serde_json::to_value(&preferences.short_links)),
+        ("schemes", serde_json::to_value(&preferences.schemes)),
+        ("workspaces", serde_json::to_value(&preferences.workspaces)),
+        (
+            "activeWorkspace",
+            serde_json::to_value(&preferences.active_workspace),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name, value.unwrap_or_default()))
+    .collect()
+}
+
+fn rule_entries(rules: &RulesSnapshot) -> Vec<(RuleChange, serde_json::Value)> {
+    let domain = rules.domain_rules.iter().map(|rule| {
+        let change = RuleChange {
+            id: rule.id.clone(),
+            kind: RuleKind::Domain,
+            label: rule.effective_pattern().to_string(),
+        };
+        (change, serde_json::to_value(rule).unwrap_or_default())
+    });
+    let file_type = rules.file_type_rules.iter().map(|rule| {
+        let change = RuleChange {
+            id: rule.id.clone(),
+            kind: RuleKind::FileType,
+            label: rule.extension.clone(),
+        };
+        (change, serde_json::to_value(rule).unwrap_or_default())
+    });
+    domain.chain(file_type).collect()
+}
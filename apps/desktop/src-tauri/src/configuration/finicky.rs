@@ -0,0 +1,805 @@
+use super::document::{ConfigDocument, CONFIG_VERSION};
+use crate::domain::models::RulePolicy;
+use crate::preferences::{FallbackPreference, Preferences, ProfilePreference};
+use crate::rules::validation::validate_pattern;
+use crate::rules::{DomainMatchType, DomainRule, RulesSnapshot};
+use serde::Serialize;
+
+/// Browser named by a Finicky handler, as written in the config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinickyBrowser {
+    pub name: String,
+    pub profile: Option<String>,
+}
+
+/// A Finicky browser mapped onto one of ours by the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedBrowser {
+    pub id: String,
+    pub name: String,
+    pub profile_label: Option<String>,
+    pub profile_directory: Option<String>,
+}
+
+/// A handler, or part of one, that could not be turned into a rule.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UntranslatedHandler {
+    /// 1-based position in the `handlers` array, or 0 for top-level settings like `defaultBrowser`.
+    pub handler: usize,
+    pub reason: String,
+    pub source: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinickyImport {
+    pub document: ConfigDocument,
+    pub untranslated: Vec<UntranslatedHandler>,
+}
+
+/// Statically translates the common subset of a Finicky config into a configuration document.
+///
+/// Only literal matchers (strings, wildcards, regular expressions and
+/// `finicky.matchHostnames`/`matchDomains`) and literal browsers are understood; anything
+/// computed at runtime is reported back instead of guessed at.
+pub fn translate_finicky_config(
+    contents: &str,
+    resolve: impl Fn(&FinickyBrowser) -> ResolvedBrowser,
+) -> Result<FinickyImport, String> {
+    let config = parse_config(contents)?;
+    let mut untranslated = Vec::new();
+    let mut rules = RulesSnapshot::default();
+
+    let handlers = match config.get("handlers") {
+        Some(JsValue::Array(handlers)) => handlers.as_slice(),
+        Some(_) => return Err("Finicky `handlers` must be an array.".to_string()),
+        None => &[],
+    };
+
+    for (index, node) in handlers.iter().enumerate() {
+        let handler = index + 1;
+        let mut report = |reason: String| {
+            untranslated.push(UntranslatedHandler {
+                handler,
+                reason,
+                source: snippet(&node.source),
+            })
+        };
+
+        let JsValue::Object(_) = &node.value else {
+            report("Handler is not an object literal.".to_string());
+            continue;
+        };
+
+        let browser = match node.value.get("browser").map(browser_of) {
+            Some(Ok(browser)) => resolve(&browser),
+            Some(Err(reason)) => {
+                report(reason);
+                continue;
+            }
+            None => {
+                report("Handler has no `browser`.".to_string());
+                continue;
+            }
+        };
+
+        let Some(matcher) = node.value.get("match") else {
+            report("Handler has no `match`.".to_string());
+            continue;
+        };
+
+        for pattern in patterns_of(matcher) {
+            let (match_type, pattern) = match pattern {
+                Ok(pattern) => pattern,
+                Err(reason) => {
+                    report(reason);
+                    continue;
+                }
+            };
+
+            if let Err(err) = validate_pattern(match_type, &pattern) {
+                report(err);
+                continue;
+            }
+
+            let id = format!("finicky:{}:{pattern}", match_type.as_str());
+            if rules.domain_rules.iter().any(|rule| rule.id == id) {
+                report(format!(
+                    "Pattern '{pattern}' is already handled by an earlier handler."
+                ));
+                continue;
+            }
+
+            rules.domain_rules.push(DomainRule {
+                id,
+                pattern,
+                domain: None,
+                match_type: Some(match_type),
+                browser_id: Some(browser.id.clone()),
+                browser_label: browser_label(&browser),
+                policy: RulePolicy::Always,
+                latency: "Auto".to_string(),
+                enabled: true,
+                workspace: None,
+                rewrite: None,
+                conditions: Vec::new(),
+            });
+        }
+    }
+
+    let fallback = match config.get("defaultBrowser").map(browser_of) {
+        Some(Ok(browser)) => {
+            let browser = resolve(&browser);
+            let profile = (browser.profile_label.is_some() || browser.profile_directory.is_some())
+                .then(|| ProfilePreference {
+                    label: browser.profile_label.clone(),
+                    directory: browser.profile_directory.clone(),
+                });
+            Some(FallbackPreference {
+                browser_id: browser
+                    .id
+                    .split_once("__")
+                    .map(|(base, _)| base.to_string()),
+                browser: browser.name,
+                profile,
+            })
+        }
+        Some(Err(reason)) => {
+            untranslated.push(UntranslatedHandler {
+                handler: 0,
+                reason: format!("defaultBrowser: {reason}"),
+                source: String::new(),
+            });
+            None
+        }
+        None => None,
+    };
+
+    Ok(FinickyImport {
+        document: ConfigDocument {
+            version: CONFIG_VERSION,
+            exported_at: None,
+            preferences: Preferences {
+                fallback,
+                ..Preferences::default()
+            },
+            rules,
+        },
+        untranslated,
+    })
+}
+
+fn browser_label(browser: &ResolvedBrowser) -> String {
+    match browser
+        .profile_label
+        .as_deref()
+        .or(browser.profile_directory.as_deref())
+    {
+        Some(profile) => format!("{} · {profile}", browser.name),
+        None => browser.name.clone(),
+    }
+}
+
+fn browser_of(value: &JsValue) -> Result<FinickyBrowser, String> {
+    match value {
+        JsValue::Str(name) => Ok(FinickyBrowser {
+            name: app_name(name),
+            profile: None,
+        }),
+        JsValue::Object(_) => {
+            let name = match value.get("name") {
+                Some(JsValue::Str(name)) => app_name(name),
+                _ => return Err("Browser object has no literal `name`.".to_string()),
+            };
+            let profile = match value.get("profile") {
+                Some(JsValue::Str(profile)) if !profile.trim().is_empty() => {
+                    Some(profile.trim().to_string())
+                }
+                Some(JsValue::Str(_)) | Some(JsValue::Null) | None => None,
+                Some(_) => return Err("Browser `profile` is not a string literal.".to_string()),
+            };
+            Ok(FinickyBrowser { name, profile })
+        }
+        // Finicky opens the first browser of a list that is running; the first is the intent.
+        JsValue::Array(browsers) => match browsers.first() {
+            Some(first) => browser_of(&first.value),
+            None => Err("Browser list is empty.".to_string()),
+        },
+        JsValue::Opaque(kind) => Err(format!(
+            "Browser is a {kind} and cannot be translated statically."
+        )),
+        _ => Err("Browser is not a string or object literal.".to_string()),
+    }
+}
+
+/// Maps macOS bundle identifiers Finicky accepts onto application names.
+fn app_name(name: &str) -> String {
+    let name = name.trim();
+    let mapped = match name.to_ascii_lowercase().as_str() {
+        "com.google.chrome" => "Google Chrome",
+        "com.google.chrome.beta" => "Google Chrome Beta",
+        "com.google.chrome.canary" => "Google Chrome Canary",
+        "org.chromium.chromium" => "Chromium",
+        "org.mozilla.firefox" => "Firefox",
+        "org.mozilla.firefoxdeveloperedition" => "Firefox Developer Edition",
+        "com.apple.safari" => "Safari",
+        "com.microsoft.edgemac" => "Microsoft Edge",
+        "com.brave.browser" => "Brave Browser",
+        "com.vivaldi.vivaldi" => "Vivaldi",
+        "company.thebrowser.browser" => "Arc",
+        _ => name,
+    };
+    mapped.to_string()
+}
+
+fn patterns_of(value: &JsValue) -> Vec<Result<(DomainMatchType, String), String>> {
+    match value {
+        JsValue::Str(pattern) => vec![url_pattern(pattern)],
+        JsValue::Regex(pattern) => vec![Ok((DomainMatchType::Regex, pattern.clone()))],
+        JsValue::Array(items) => items
+            .iter()
+            .flat_map(|item| patterns_of(&item.value))
+            .collect(),
+        JsValue::Call { callee, args }
+            if callee.ends_with("matchHostnames") || callee.ends_with("matchDomains") =>
+        {
+            let hosts = match args.first() {
+                Some(JsValue::Array(items)) => items.iter().map(|item| &item.value).collect(),
+                Some(other) => vec![other],
+                None => Vec::new(),
+            };
+            hosts.into_iter().map(hostname_pattern).collect()
+        }
+        JsValue::Call { callee, .. } => {
+            vec![Err(format!("Matcher `{callee}(...)` is not supported."))]
+        }
+        JsValue::Opaque(kind) => vec![Err(format!(
+            "Matcher is a {kind} and cannot be translated statically."
+        ))],
+        _ => vec![Err(
+            "Matcher is not a string, regular expression or array.".to_string()
+        )],
+    }
+}
+
+/// Finicky string matchers are URL globs; the scheme is dropped since rules match host and path.
+fn url_pattern(pattern: &str) -> Result<(DomainMatchType, String), String> {
+    let trimmed = pattern.trim();
+    let rest = match trimmed.split_once("://") {
+        Some(("http" | "https" | "*" | "http*", rest)) => rest,
+        Some((scheme, _)) => return Err(unsupported_scheme(trimmed, scheme)),
+        None => {
+            // `mailto:*` and friends, as opposed to a `host:port` pair.
+            if let Some((scheme, rest)) = trimmed.split_once(':') {
+                let is_scheme = scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphabetic() || c == '+' || c == '-');
+                if is_scheme && !rest.starts_with(|c: char| c.is_ascii_digit()) {
+                    return Err(unsupported_scheme(trimmed, scheme));
+                }
+            }
+            trimmed
+        }
+    };
+
+    let rest = rest.trim_end_matches('/');
+    if rest.contains('*') || rest.contains('/') {
+        Ok((DomainMatchType::Wildcard, rest.to_string()))
+    } else {
+        Ok((DomainMatchType::Host, rest.to_string()))
+    }
+}
+
+fn unsupported_scheme(pattern: &str, scheme: &str) -> String {
+    format!("Matcher '{pattern}' targets the '{scheme}' scheme, which rules cannot match.")
+}
+
+fn hostname_pattern(value: &JsValue) -> Result<(DomainMatchType, String), String> {
+    match value {
+        JsValue::Str(host) => Ok((DomainMatchType::Host, host.trim().to_string())),
+        JsValue::Regex(pattern) => {
+            // Rules test regexes against the whole URL, so pin the host expression in place
+            // and keep its wildcards from running on into the path.
+            let body = pattern.strip_prefix('^').unwrap_or(pattern);
+            let body = body.strip_suffix('$').unwrap_or(body);
+            let lead = if pattern.starts_with('^') {
+                ""
+            } else {
+                "[^/?#@:]*"
+            };
+            let tail = if pattern.ends_with('$') {
+                ""
+            } else {
+                "[^/?#@:]*"
+            };
+            Ok((
+                DomainMatchType::Regex,
+                format!(
+                    "^[a-z][a-z0-9+.-]*://(?:[^/@]*@)?{lead}(?:{}){tail}(?::\\d+)?(?:[/?#]|$)",
+                    confine_dots(body)
+                ),
+            ))
+        }
+        _ => Err("Hostname matcher is not a string or regular expression.".to_string()),
+    }
+}
+
+/// Rewrites unescaped `.` outside character classes to match host characters only.
+fn confine_dots(pattern: &str) -> String {
+    let mut confined = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                confined.push(c);
+                confined.extend(chars.next());
+            }
+            '[' => {
+                in_class = true;
+                confined.push(c);
+            }
+            ']' => {
+                in_class = false;
+                confined.push(c);
+            }
+            '.' if !in_class => confined.push_str("[^/?#@:]"),
+            c => confined.push(c),
+        }
+    }
+    confined
+}
+
+fn snippet(source: &str) -> String {
+    let collapsed = source.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > 120 {
+        format!("{}…", collapsed.chars().take(119).collect::<String>())
+    } else {
+        collapsed
+    }
+}
+
+/// The subset of JavaScript values a Finicky config is written in.
+#[derive(Debug, Clone)]
+enum JsValue {
+    Str(String),
+    Regex(String),
+    Literal,
+    Null,
+    Array(Vec<JsNode>),
+    Object(Vec<(String, JsValue)>),
+    Call {
+        callee: String,
+        args: Vec<JsValue>,
+    },
+    /// Code that only has meaning at runtime, e.g. a function; holds a short description.
+    Opaque(&'static str),
+}
+
+#[derive(Debug, Clone)]
+struct JsNode {
+    value: JsValue,
+    source: String,
+}
+
+impl JsValue {
+    fn get(&self, key: &str) -> Option<&JsValue> {
+        match self {
+            JsValue::Object(entries) => entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+fn parse_config(contents: &str) -> Result<JsValue, String> {
+    let mut parser = Parser::new(contents);
+    let start = ["module.exports", "export default"]
+        .iter()
+        .find_map(|marker| parser.find_after(marker))
+        .ok_or_else(|| {
+            "No `module.exports` or `export default` found; is this a Finicky config?".to_string()
+        })?;
+    parser.pos = start;
+    parser.skip_trivia();
+    if parser.peek() == Some('=') {
+        parser.pos += 1;
+    }
+
+    match parser.parse_value()? {
+        value @ JsValue::Object(_) => Ok(value),
+        _ => Err("The exported Finicky config is not an object literal.".to_string()),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(offset, c)| self.peek_at(offset) == Some(c))
+    }
+
+    /// Position just past the first occurrence of `marker` outside strings and comments.
+    fn find_after(&mut self, marker: &str) -> Option<usize> {
+        self.pos = 0;
+        while self.pos < self.chars.len() {
+            self.skip_trivia();
+            if self.starts_with(marker) {
+                return Some(self.pos + marker.chars().count());
+            }
+            match self.peek() {
+                Some(quote @ ('"' | '\'' | '`')) => {
+                    let _ = self.parse_string(quote);
+                }
+                Some(_) => self.pos += 1,
+                None => break,
+            }
+        }
+        None
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(c), _) if c.is_whitespace() => self.pos += 1,
+                (Some('/'), Some('/')) => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some('/'), Some('*')) => {
+                    self.pos += 2;
+                    while self.pos < self.chars.len() && !self.starts_with("*/") {
+                        self.pos += 1;
+                    }
+                    self.pos = (self.pos + 2).min(self.chars.len());
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_trivia();
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{expected}'")))
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        let line = self.chars[..self.pos.min(self.chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count()
+            + 1;
+        format!("Could not parse Finicky config at line {line}: {message}.")
+    }
+
+    fn parse_value(&mut self) -> Result<JsValue, String> {
+        self.skip_trivia();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some(quote @ ('"' | '\'' | '`')) => self.parse_string(quote),
+            Some('/') => self.parse_regex(),
+            Some('(') => {
+                self.skip_balanced('(', ')')?;
+                self.skip_trivia();
+                if self.starts_with("=>") {
+                    self.pos += 2;
+                    self.skip_expression()?;
+                    Ok(JsValue::Opaque("function"))
+                } else {
+                    Ok(JsValue::Opaque("computed expression"))
+                }
+            }
+            Some(c) if is_ident_start(c) => self.parse_identifier_value(),
+            Some(c) if c.is_ascii_digit() || c == '-' => {
+                self.skip_expression()?;
+                Ok(JsValue::Literal)
+            }
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of file")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsValue, String> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(JsValue::Object(entries));
+                }
+                Some('.') if self.starts_with("...") => {
+                    self.pos += 3;
+                    self.skip_expression()?;
+                }
+                Some(quote @ ('"' | '\'')) => {
+                    let key = match self.parse_string(quote)? {
+                        JsValue::Str(key) => key,
+                        _ => String::new(),
+                    };
+                    self.expect(':')?;
+                    entries.push((key, self.parse_value()?));
+                }
+                Some(c) if is_ident_start(c) || c.is_ascii_digit() => {
+                    let key = self.parse_identifier();
+                    self.skip_trivia();
+                    match self.peek() {
+                        Some(':') => {
+                            self.pos += 1;
+                            entries.push((key, self.parse_value()?));
+                        }
+                        // Method shorthand: `match(url) { ... }`.
+                        Some('(') => {
+                            self.skip_balanced('(', ')')?;
+                            self.skip_trivia();
+                            self.skip_balanced('{', '}')?;
+                            entries.push((key, JsValue::Opaque("function")));
+                        }
+                        _ => entries.push((key, JsValue::Opaque("variable reference"))),
+                    }
+                }
+                Some('[') => {
+                    // Computed key; keep the value parse going but drop the entry.
+                    self.skip_balanced('[', ']')?;
+                    self.expect(':')?;
+                    self.parse_value()?;
+                }
+                _ => return Err(self.error("expected a property name")),
+            }
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsValue, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(JsValue::Array(items));
+            }
+
+            let start = self.pos;
+            let value = self.parse_value()?;
+            let source = self.chars[start..self.pos].iter().collect();
+            items.push(JsNode { value, source });
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<JsValue, String> {
+        self.pos += 1;
+        let mut value = String::new();
+        let mut interpolated = false;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.pos += 1;
+                    value.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        other => other,
+                    });
+                }
+                '$' if quote == '`' && self.peek() == Some('{') => {
+                    interpolated = true;
+                    self.skip_balanced('{', '}')?;
+                }
+                c if c == quote => {
+                    return Ok(if interpolated {
+                        JsValue::Opaque("template string")
+                    } else {
+                        JsValue::Str(value)
+                    });
+                }
+                c => value.push(c),
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    fn parse_regex(&mut self) -> Result<JsValue, String> {
+        self.pos += 1;
+        let mut pattern = String::new();
+        let mut in_class = false;
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    let Some(escaped) = self.peek() else { break };
+                    self.pos += 1;
+                    // `\/` only exists to satisfy the JS literal syntax.
+                    if escaped != '/' {
+                        pattern.push('\\');
+                    }
+                    pattern.push(escaped);
+                }
+                '[' => {
+                    in_class = true;
+                    pattern.push(c);
+                }
+                ']' => {
+                    in_class = false;
+                    pattern.push(c);
+                }
+                '/' if !in_class => {
+                    while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                        self.pos += 1;
+                    }
+                    return Ok(JsValue::Regex(pattern));
+                }
+                '\n' => break,
+                c => pattern.push(c),
+            }
+        }
+        Err(self.error("unterminated regular expression"))
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn parse_identifier_value(&mut self) -> Result<JsValue, String> {
+        let mut path = self.parse_identifier();
+        match path.as_str() {
+            "true" | "false" => return Ok(JsValue::Literal),
+            "null" | "undefined" => return Ok(JsValue::Null),
+            "function" | "async" => {
+                self.skip_expression()?;
+                return Ok(JsValue::Opaque("function"));
+            }
+            _ => {}
+        }
+
+        loop {
+            self.skip_trivia();
+            match self.peek() {
+                Some('.') => {
+                    self.pos += 1;
+                    self.skip_trivia();
+                    path.push('.');
+                    path.push_str(&self.parse_identifier());
+                }
+                Some('(') => {
+                    self.pos += 1;
+                    let mut args = Vec::new();
+                    loop {
+                        self.skip_trivia();
+                        if self.peek() == Some(')') {
+                            self.pos += 1;
+                            break;
+                        }
+                        args.push(self.parse_value()?);
+                        self.skip_trivia();
+                        match self.peek() {
+                            Some(',') => self.pos += 1,
+                            Some(')') => {}
+                            _ => return Err(self.error("expected ',' or ')'")),
+                        }
+                    }
+                    self.skip_trivia();
+                    if matches!(self.peek(), Some(c) if c != ',' && c != '}' && c != ']' && c != ')')
+                    {
+                        self.skip_expression()?;
+                        return Ok(JsValue::Opaque("computed expression"));
+                    }
+                    return Ok(JsValue::Call { callee: path, args });
+                }
+                Some('=') if self.starts_with("=>") => {
+                    self.pos += 2;
+                    self.skip_expression()?;
+                    return Ok(JsValue::Opaque("function"));
+                }
+                Some(',' | '}' | ']' | ')') | None => {
+                    return Ok(JsValue::Opaque("variable reference"))
+                }
+                Some(_) => {
+                    self.skip_expression()?;
+                    return Ok(JsValue::Opaque("computed expression"));
+                }
+            }
+        }
+    }
+
+    /// Skips from the current position to the end of the enclosing expression.
+    fn skip_expression(&mut self) -> Result<(), String> {
+        let mut depth = 0usize;
+        let mut previous = '(';
+        loop {
+            self.skip_trivia();
+            let Some(c) = self.peek() else {
+                return Ok(());
+            };
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => return Ok(()),
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => return Ok(()),
+                '"' | '\'' | '`' => {
+                    self.parse_string(c)?;
+                    previous = '"';
+                    continue;
+                }
+                '/' if "(,=:[!&|?{};+-*%<>~^".contains(previous) => {
+                    self.parse_regex()?;
+                    previous = '/';
+                    continue;
+                }
+                _ => {}
+            }
+            previous = c;
+            self.pos += 1;
+        }
+    }
+
+    fn skip_balanced(&mut self, open: char, close: char) -> Result<(), String> {
+        self.skip_trivia();
+        if self.peek() == Some(open) {
+            self.pos += 1;
+        }
+        self.skip_expression()?;
+        while self.peek() == Some(',') {
+            self.pos += 1;
+            self.skip_expression()?;
+        }
+        self.expect(close)
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
@@ -0,0 +1,203 @@
+pub mod document;
+pub mod finicky;
+
+use crate::browser_descriptor::BrowserDescriptor;
+use crate::browser_details::{
+    browser_catalog, browser_catalog_id, get_profiles_for_browser, parse_browser_kind,
+};
+use crate::preferences::{Preferences, PreferencesState};
+use crate::routing::normalize_browser_key;
+use crate::rules::{validate_browser_id, RulesState};
+use crate::{platform, schemes};
+use chrono::Utc;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+pub use document::{
+    apply_import, diff_configuration, ConfigDiff, ConfigDocument, ConfigFormat, ImportMode,
+    CONFIG_VERSION,
+};
+pub use finicky::{translate_finicky_config, FinickyBrowser, ResolvedBrowser, UntranslatedHandler};
+
+/// Outcome of an import; `applied` is false for dry runs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub applied: bool,
+    pub diff: ConfigDiff,
+    /// Non-fatal problems, such as rules targeting browsers missing on this machine.
+    pub warnings: Vec<String>,
+}
+
+/// Serializes the current rules and preferences into a portable document.
+pub async fn export_configuration(app: &AppHandle, format: ConfigFormat) -> Result<String, String> {
+    let (preferences, rules) = current_configuration(app).await?;
+    ConfigDocument::new(preferences, rules, Utc::now().to_rfc3339()).to_string(format)
+}
+
+/// Parses and validates a document, then applies it unless `dry_run` is set.
+pub async fn import_configuration(
+    app: &AppHandle,
+    contents: &str,
+    format: Option<ConfigFormat>,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let document = ConfigDocument::parse(contents, format)?.normalized()?;
+    apply_document(app, document, mode, dry_run).await
+}
+
+/// Finicky handlers translated into rules, plus the ones that had to be left behind.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinickyImportReport {
+    #[serde(flatten)]
+    pub import: ImportReport,
+    pub untranslated: Vec<UntranslatedHandler>,
+}
+
+/// Imports the statically translatable part of a Finicky config.
+pub async fn import_finicky_configuration(
+    app: &AppHandle,
+    contents: &str,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<FinickyImportReport, String> {
+    let browsers = browser_catalog(app).await.descriptors;
+    let translated = translate_finicky_config(contents, |browser| {
+        resolve_finicky_browser(&browsers, browser)
+    })?;
+    let document = translated.document.normalized()?;
+    let import = apply_document(app, document, mode, dry_run).await?;
+    Ok(FinickyImportReport {
+        import,
+        untranslated: translated.untranslated,
+    })
+}
+
+async fn apply_document(
+    app: &AppHandle,
+    document: ConfigDocument,
+    mode: ImportMode,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let browsers = browser_catalog(app).await.descriptors;
+    let mut warnings = browser_warnings(&browsers, &document);
+
+    let (current_preferences, current_rules) = current_configuration(app).await?;
+    let (preferences, rules) = apply_import(&current_preferences, &current_rules, document, mode);
+    let diff = diff_configuration(&current_preferences, &current_rules, &preferences, &rules);
+    if !preferences.schemes.is_empty() && !platform::claims_custom_schemes() {
+        warnings.push(
+            "Scheme handlers are kept, but only http and https links can be handled on this system."
+                .to_string(),
+        );
+    }
+
+    if !dry_run {
+        let rules_state = app
+            .try_state::<RulesState>()
+            .ok_or_else(|| "Rules state not initialised".to_string())?;
+        let preferences_state = app
+            .try_state::<PreferencesState>()
+            .ok_or_else(|| "Preferences state not initialised".to_string())?;
+
+        let schemes_changed = diff
+            .preferences
+            .iter()
+            .any(|change| change.name == "schemes");
+        let handled = schemes::handled_schemes(&preferences.schemes);
+        rules_state.replace(app, rules).await?;
+        preferences_state.replace(app, preferences).await?;
+        crate::workspaces::sync_workspace(app).await?;
+        if schemes_changed {
+            platform::register_as_browser(app, &handled)?;
+        }
+    }
+
+    Ok(ImportReport {
+        mode,
+        applied: !dry_run,
+        diff,
+        warnings,
+    })
+}
+
+async fn current_configuration(
+    app: &AppHandle,
+) -> Result<(Preferences, crate::rules::RulesSnapshot), String> {
+    let rules = app
+        .try_state::<RulesState>()
+        .ok_or_else(|| "Rules state not initialised".to_string())?
+        .snapshot()
+        .await;
+    let preferences = match app.try_state::<PreferencesState>() {
+        Some(state) => state.snapshot().await,
+        None => Preferences::default(),
+    };
+    Ok((preferences, rules))
+}
+
+/// Imported rules may target browsers this machine lacks; they are kept and fall back at routing time.
+fn browser_warnings(browsers: &[BrowserDescriptor], document: &ConfigDocument) -> Vec<String> {
+    let domain = document
+        .rules
+        .domain_rules
+        .iter()
+        .map(|rule| (rule.effective_pattern(), rule.browser_id.as_deref()));
+    let file_type = document
+        .rules
+        .file_type_rules
+        .iter()
+        .map(|rule| (rule.extension.as_str(), rule.browser_id.as_deref()));
+
+    domain
+        .chain(file_type)
+        .filter_map(|(label, browser_id)| {
+            validate_browser_id(browsers, browser_id?)
+                .err()
+                .map(|err| format!("Rule '{label}': {err}"))
+        })
+        .collect()
+}
+
+/// Matches a Finicky browser name and profile against what is installed here.
+///
+/// Unknown browsers keep their Finicky name so the rules start working once it is installed.
+fn resolve_finicky_browser(
+    browsers: &[BrowserDescriptor],
+    browser: &FinickyBrowser,
+) -> ResolvedBrowser {
+    let wanted_key = normalize_browser_key(&browser.name);
+    let wanted_kind = parse_browser_kind(&browser.name);
+    let installed = browsers.iter().find(|installed| {
+        normalize_browser_key(&installed.display_name) == wanted_key
+            || (wanted_kind.is_some() && parse_browser_kind(&installed.display_name) == wanted_kind)
+    });
+    let (browser_id, name) = match installed {
+        Some(installed) => (installed.id.clone(), installed.display_name.clone()),
+        None => (browser.name.clone(), browser.name.clone()),
+    };
+
+    let profile = browser.profile.as_deref().and_then(|wanted| {
+        get_profiles_for_browser(browsers, &name)
+            .into_iter()
+            .find(|profile| {
+                profile.directory.eq_ignore_ascii_case(wanted)
+                    || profile.display_name.eq_ignore_ascii_case(wanted)
+            })
+    });
+
+    let (profile_label, profile_directory) = match profile {
+        Some(profile) => (Some(profile.display_name), Some(profile.directory)),
+        None => (None, browser.profile.clone()),
+    };
+
+    ResolvedBrowser {
+        id: browser_catalog_id(&browser_id, profile_directory.as_deref()),
+        name,
+        profile_label,
+        profile_directory,
+    }
+}
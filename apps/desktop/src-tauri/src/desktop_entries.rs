@@ -0,0 +1,348 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Our own desktop entry, which claims http(s) but must never be offered as a browser.
+pub const OWN_DESKTOP_FILE: &str = "open-with-browser.desktop";
+const HTTP_HANDLER: &str = "x-scheme-handler/http";
+
+/// A browser found through an XDG desktop entry or `$BROWSER`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesktopBrowser {
+    /// Desktop file id such as `firefox.desktop`, or `$BROWSER` for the environment override.
+    pub id: String,
+    pub name: String,
+    /// The `Exec` command split into arguments, field codes still in place.
+    pub exec: Vec<String>,
+    pub icon: Option<String>,
+    pub try_exec: Option<String>,
+    /// The desktop file the entry was read from.
+    pub path: Option<PathBuf>,
+}
+
+/// How to start a browser: the link goes between `leading_args` and `trailing_args`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecTemplate {
+    pub program: String,
+    pub leading_args: Vec<String>,
+    pub trailing_args: Vec<String>,
+}
+
+impl DesktopBrowser {
+    /// Expands the `Exec` field codes, leaving the position of the link open.
+    ///
+    /// The first `%u`, `%U`, `%f`, `%F` or `%s` marks where the link goes; without one it
+    /// is appended. `%i`, `%c` and `%k` expand as the desktop entry specification says.
+    pub fn exec_template(&self) -> Option<ExecTemplate> {
+        let (program, args) = self.exec.split_first()?;
+        let mut template = ExecTemplate {
+            program: program.clone(),
+            leading_args: Vec::new(),
+            trailing_args: Vec::new(),
+        };
+        let mut after_link = false;
+
+        for arg in args {
+            let expanded = match arg.as_str() {
+                "%u" | "%U" | "%f" | "%F" | "%s" if !after_link => {
+                    after_link = true;
+                    continue;
+                }
+                "%u" | "%U" | "%f" | "%F" | "%s" => continue,
+                "%i" => match &self.icon {
+                    Some(icon) => vec!["--icon".to_string(), icon.clone()],
+                    None => Vec::new(),
+                },
+                _ => vec![expand_inline_codes(arg, self)],
+            };
+            if after_link {
+                template.trailing_args.extend(expanded);
+            } else {
+                template.leading_args.extend(expanded);
+            }
+        }
+
+        Some(template)
+    }
+
+    /// The executable `Exec` starts, without any arguments.
+    pub fn program(&self) -> Option<&str> {
+        self.exec.first().map(String::as_str)
+    }
+}
+
+fn expand_inline_codes(arg: &str, entry: &DesktopBrowser) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => expanded.push('%'),
+            Some('c') => expanded.push_str(&entry.name),
+            Some('k') => {
+                if let Some(path) = &entry.path {
+                    expanded.push_str(&path.to_string_lossy());
+                }
+            }
+            // Deprecated and link codes have nothing to expand to inside a larger argument.
+            _ => {}
+        }
+    }
+    expanded
+}
+
+/// Reads a desktop entry, returning it only when it is an application that opens http links.
+pub fn parse_desktop_entry(id: &str, contents: &str) -> Option<DesktopBrowser> {
+    let mut in_entry = false;
+    let mut name = None;
+    let mut exec = None;
+    let mut icon = None;
+    let mut try_exec = None;
+    let mut is_application = false;
+    let mut handles_http = false;
+    let mut hidden = false;
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_entry {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unescape_value(value.trim());
+        match key.trim() {
+            "Type" => is_application = value == "Application",
+            "Name" => name = Some(value),
+            "Exec" => exec = Some(value),
+            "Icon" => icon = Some(value).filter(|icon| !icon.is_empty()),
+            "TryExec" => try_exec = Some(value).filter(|program| !program.is_empty()),
+            "Hidden" => hidden = value.eq_ignore_ascii_case("true"),
+            "MimeType" => handles_http = value.split(';').any(|mime| mime.trim() == HTTP_HANDLER),
+            _ => {}
+        }
+    }
+
+    if !is_application || !handles_http || hidden || id == OWN_DESKTOP_FILE {
+        return None;
+    }
+    let exec = split_exec(&exec?);
+    if exec.is_empty() {
+        return None;
+    }
+
+    Some(DesktopBrowser {
+        id: id.to_string(),
+        name: name.filter(|name| !name.is_empty())?,
+        exec,
+        icon,
+        try_exec,
+        path: None,
+    })
+}
+
+/// Undoes the escapes allowed in string values (`\s`, `\n`, `\t`, `\r`, `\\`).
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => {
+                // Keep other escapes for the Exec quoting rules to interpret.
+                if other != '\\' {
+                    unescaped.push('\\');
+                }
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Splits an `Exec` value into arguments, honouring double quotes and their escapes.
+pub fn split_exec(exec: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            '\\' if quoted => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Finds browsers in the `applications/` directory of each of `data_dirs`, in order.
+///
+/// An id found in an earlier directory hides the same id later on, as XDG lookups do.
+/// Commands listed in `browser_env` (the `$BROWSER` variable) come first.
+pub fn discover_in(data_dirs: &[PathBuf], browser_env: Option<&str>) -> Vec<DesktopBrowser> {
+    let mut seen = Vec::new();
+    let mut browsers = Vec::new();
+
+    for data_dir in data_dirs {
+        let applications = data_dir.join("applications");
+        let mut entries = Vec::new();
+        collect_entries(&applications, &applications, &mut entries);
+        entries.sort();
+
+        for (id, path) in entries {
+            if seen.contains(&id) {
+                continue;
+            }
+            seen.push(id.clone());
+
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Some(mut browser) = parse_desktop_entry(&id, &contents) {
+                browser.path = Some(path);
+                if browser.try_exec.as_deref().is_none_or(program_exists) {
+                    browsers.push(browser);
+                }
+            }
+        }
+    }
+
+    let preferred = browser_env
+        .map(|value| env_browsers(value, &browsers))
+        .unwrap_or_default();
+    let mut ordered = preferred.clone();
+    ordered.extend(browsers.into_iter().filter(|b| !preferred.contains(b)));
+    ordered
+}
+
+/// Browsers from the XDG data directories of the current user and system.
+pub fn discover() -> Vec<DesktopBrowser> {
+    let browser_env = std::env::var("BROWSER").ok();
+    discover_in(&data_dirs(), browser_env.as_deref())
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, with the specification's defaults.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+    let system = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    let system_dirs = system.split(':').filter(|dir| !dir.is_empty());
+    data_home
+        .into_iter()
+        .chain(system_dirs.map(PathBuf::from))
+        .collect()
+}
+
+/// Desktop file ids are paths below `applications/` with `/` replaced by `-`.
+fn collect_entries(root: &Path, dir: &Path, entries: &mut Vec<(String, PathBuf)>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in read_dir.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_entries(root, &path, entries);
+        } else if path.extension().is_some_and(|ext| ext == "desktop") {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let id = relative.to_string_lossy().replace('/', "-");
+                entries.push((id, path));
+            }
+        }
+    }
+}
+
+/// Resolves `$BROWSER`, a `:`-separated list of commands where `%s` stands for the link.
+///
+/// A command whose program matches a desktop entry selects that entry; any other command
+/// becomes an entry of its own.
+fn env_browsers(value: &str, browsers: &[DesktopBrowser]) -> Vec<DesktopBrowser> {
+    let mut preferred: Vec<DesktopBrowser> = Vec::new();
+    for command in value.split(':').map(str::trim).filter(|c| !c.is_empty()) {
+        let exec = split_exec(command);
+        let Some(program) = exec.first() else {
+            continue;
+        };
+        let name = program_name(program);
+        let matched = browsers
+            .iter()
+            .find(|browser| browser.program().map(program_name) == Some(name));
+        let browser = match matched {
+            Some(browser) => browser.clone(),
+            None if program_exists(program) => DesktopBrowser {
+                id: "$BROWSER".to_string(),
+                name: name.to_string(),
+                exec,
+                icon: None,
+                try_exec: None,
+                path: None,
+            },
+            None => continue,
+        };
+        if !preferred.contains(&browser) {
+            preferred.push(browser);
+        }
+    }
+    preferred
+}
+
+fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+/// Whether `program` is an existing path or an executable on `PATH`.
+pub fn program_exists(program: &str) -> bool {
+    resolve_program(program).is_some()
+}
+
+/// The full path of `program`, looked up on `PATH` when it is a bare name.
+pub fn resolve_program(program: &str) -> Option<PathBuf> {
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return path.is_file().then_some(path);
+    }
+    which::which(program).ok()
+}
@@ -1,9 +1,8 @@
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Rule {
-    pub id: Uuid,
+    pub id: String,
     pub rule_name: String,
     pub priority: i32,
     pub conditions: Vec<Condition>,
@@ -23,4 +22,15 @@ pub struct Action {
     pub profile: String,
     pub browser: String,
     pub url: String,
+    #[serde(default)]
+    pub policy: RulePolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RulePolicy {
+    #[default]
+    Always,
+    #[serde(rename = "Just once")]
+    JustOnce,
+    Fallback,
 }
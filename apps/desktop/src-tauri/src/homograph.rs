@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use unicode_security::{skeleton, RestrictionLevel, RestrictionLevelDetection};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkWarningKind {
+    /// A label mixes scripts that do not normally appear together, e.g. Latin and Cyrillic.
+    MixedScript,
+    /// The host is written in lookalike characters that read as a different, plain host.
+    Confusable,
+}
+
+/// Why an incoming link's host looks like an attempt to imitate another site.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkWarning {
+    pub kind: LinkWarningKind,
+    /// The host as a user would read it.
+    pub display_host: String,
+    /// The host actually contacted, in punycode.
+    pub ascii_host: String,
+    /// The plain host the display form can be mistaken for, if any.
+    #[serde(default)]
+    pub lookalike: Option<String>,
+    pub message: String,
+}
+
+/// Checks an internationalized host for mixed scripts and lookalike characters.
+///
+/// Plain ASCII hosts and IDNs written consistently in one script pass, unless the
+/// whole host can be mistaken for a different ASCII one.
+pub fn check_host(url: &Url) -> Option<LinkWarning> {
+    let ascii_host = url.host_str()?.trim_end_matches('.').to_ascii_lowercase();
+    let (display_host, result) = idna::domain_to_unicode(&ascii_host);
+    if result.is_err() || display_host == ascii_host {
+        return None;
+    }
+
+    let mixed = display_host
+        .split('.')
+        .filter(|label| !label.is_ascii())
+        .any(|label| label.detect_restriction_level() > RestrictionLevel::ModeratelyRestrictive);
+    // Only the non-ASCII characters are folded: the skeleton of plain letters such as `m`
+    // is itself a lookalike ("rn") and would not name the site being imitated.
+    let lookalike = display_host
+        .chars()
+        .map(|c| {
+            if c.is_ascii() {
+                c.to_string()
+            } else {
+                skeleton(c.encode_utf8(&mut [0; 4])).collect()
+            }
+        })
+        .collect::<String>()
+        .to_lowercase();
+    let lookalike = (lookalike.is_ascii() && lookalike != ascii_host).then_some(lookalike);
+
+    let (kind, message) = match (mixed, &lookalike) {
+        (true, Some(lookalike)) => (
+            LinkWarningKind::MixedScript,
+            format!(
+                "'{display_host}' mixes letters from different scripts to look like {lookalike}."
+            ),
+        ),
+        (true, None) => (
+            LinkWarningKind::MixedScript,
+            format!("'{display_host}' mixes letters from different scripts."),
+        ),
+        (false, Some(lookalike)) => (
+            LinkWarningKind::Confusable,
+            format!(
+                "'{display_host}' looks like {lookalike} but is a different address ({ascii_host})."
+            ),
+        ),
+        (false, None) => return None,
+    };
+
+    Some(LinkWarning {
+        kind,
+        display_host,
+        ascii_host,
+        lookalike,
+        message,
+    })
+}
@@ -1,24 +1,38 @@
+pub mod browser_descriptor;
 pub mod browser_details;
+pub mod cleaning;
 mod commands;
+pub mod configuration;
+pub mod desktop_entries;
 pub mod diagnostics;
-mod domain;
+pub mod domain;
+pub mod homograph;
 pub mod link;
 mod platform;
-mod preferences;
+pub mod preferences;
 pub mod routing;
+pub mod rules;
+pub mod sandbox;
+pub mod schemes;
+mod tray;
+mod workspaces;
 
 use commands::{
-    clear_diagnostics, export_diagnostics, get_available_browsers, get_diagnostics,
-    get_preferences, get_profiles, is_default_browser, open_default_browser_settings,
-    register_browser_handlers, register_incoming_link, resolve_incoming_link, routing_snapshot,
-    set_fallback_browser, simulate_incoming_link,
+    clear_diagnostics, delete_rule, explain_route, export_configuration, export_diagnostics,
+    get_available_browsers, get_diagnostics, get_preferences, get_profiles, import_configuration,
+    import_finicky_configuration, is_default_browser, lint_rules, list_browsers, list_rules,
+    open_default_browser_settings, register_browser_handlers, register_incoming_link,
+    reorder_rules, resolve_incoming_link, routing_snapshot, rule_stats, set_active_workspace,
+    set_fallback_browser, set_scheme_handlers, set_short_link_expansion, set_tracking_cleanup,
+    set_workspaces, simulate_incoming_link, upsert_rule,
 };
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 use link::handle_open_urls;
 use link::{handle_cli_arguments, LinkSource};
 use routing::RoutingService;
+use std::path::Path;
 use tauri::{
-    menu::{MenuBuilder, MenuEvent, MenuItemBuilder},
+    menu::MenuEvent,
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Manager, WindowEvent,
 };
@@ -39,9 +53,14 @@ pub fn run() {
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, None))
-        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
             let args = argv.into_iter().skip(1).collect::<Vec<_>>();
-            handle_cli_arguments(&app.app_handle(), &args, LinkSource::SecondaryInstance);
+            handle_cli_arguments(
+                app.app_handle(),
+                &args,
+                LinkSource::SecondaryInstance,
+                Path::new(&cwd),
+            );
         }))
         .on_window_event(|window, event| {
             if let WindowEvent::CloseRequested { api, .. } = event {
@@ -52,32 +71,59 @@ pub fn run() {
         .manage(RoutingService::new())
         .manage(diagnostics::DiagnosticsState::default())
         .setup(|app| {
-            let args = std::env::args().skip(1).collect::<Vec<_>>();
-            handle_cli_arguments(&app.handle(), &args, LinkSource::InitialLaunch);
+            match preferences::PreferencesState::load(app.handle()) {
+                Ok(state) => {
+                    let _ = app.manage(state);
+                }
+                Err(err) => eprintln!("failed to load preferences: {err}"),
+            }
 
-            if let Err(err) = platform::register_as_browser(&app.handle()) {
+            let preferences = app
+                .try_state::<preferences::PreferencesState>()
+                .map(|state| tauri::async_runtime::block_on(state.snapshot()))
+                .unwrap_or_default();
+
+            let handled_schemes = schemes::handled_schemes(&preferences.schemes);
+            if let Err(err) = platform::register_as_browser(app.handle(), &handled_schemes) {
                 eprintln!("failed to register platform browser hooks: {err}");
             }
 
-            match preferences::PreferencesState::load(&app.handle()) {
+            let browser_catalog = browser_details::BrowserCatalogState::load();
+            let browsers = tauri::async_runtime::block_on(browser_catalog.catalog()).descriptors;
+            let _ = app.manage(browser_catalog);
+
+            match rules::RulesState::load(
+                app.handle(),
+                preferences.active_workspace.clone(),
+                &browsers,
+            ) {
                 Ok(state) => {
                     let _ = app.manage(state);
                 }
-                Err(err) => eprintln!("failed to load preferences: {err}"),
+                Err(err) => eprintln!("failed to load routing rules: {err}"),
             }
 
-            let show_item = MenuItemBuilder::with_id("show", "Show window").build(app)?;
-            let hide_item = MenuItemBuilder::with_id("hide", "Hide window").build(app)?;
-            let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+            match rules::RuleStatsState::load(app.handle()) {
+                Ok(state) => {
+                    let _ = app.manage(state);
+                }
+                Err(err) => eprintln!("failed to load rule stats: {err}"),
+            }
+
+            match cleaning::ShortLinkCacheState::load(app.handle()) {
+                Ok(state) => {
+                    let _ = app.manage(state);
+                }
+                Err(err) => eprintln!("failed to load short link cache: {err}"),
+            }
 
-            let tray_menu = MenuBuilder::new(app)
-                .item(&show_item)
-                .item(&hide_item)
-                .separator()
-                .item(&quit_item)
-                .build()?;
+            let args = std::env::args().skip(1).collect::<Vec<_>>();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            handle_cli_arguments(app.handle(), &args, LinkSource::InitialLaunch, &cwd);
 
-            let mut tray_builder = TrayIconBuilder::new()
+            let tray_menu = tray::build_menu(app, &preferences)?;
+
+            let mut tray_builder = TrayIconBuilder::with_id(tray::TRAY_ID)
                 .menu(&tray_menu)
                 .show_menu_on_left_click(true)
                 .tooltip("Open With Browser")
@@ -95,7 +141,7 @@ pub fn run() {
                         }
                     }
                     "quit" => app.exit(0),
-                    _ => {}
+                    other => tray::handle_workspace_item(app, other),
                 })
                 .on_tray_icon_event(|tray, event: TrayIconEvent| match event {
                     TrayIconEvent::Click { button, button_state, .. }
@@ -129,16 +175,32 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             greet,
             get_available_browsers,
+            list_browsers,
             get_profiles,
             routing_snapshot,
             register_incoming_link,
             resolve_incoming_link,
             simulate_incoming_link,
+            explain_route,
             is_default_browser,
             open_default_browser_settings,
             register_browser_handlers,
             get_preferences,
             set_fallback_browser,
+            set_tracking_cleanup,
+            set_short_link_expansion,
+            set_scheme_handlers,
+            set_workspaces,
+            set_active_workspace,
+            list_rules,
+            upsert_rule,
+            delete_rule,
+            reorder_rules,
+            lint_rules,
+            rule_stats,
+            export_configuration,
+            import_configuration,
+            import_finicky_configuration,
             get_diagnostics,
             clear_diagnostics,
             export_diagnostics
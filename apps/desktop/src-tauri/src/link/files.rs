@@ -0,0 +1,107 @@
+use super::text::extract_text_urls;
+use crate::schemes::WEB_SCHEMES;
+use std::path::Path;
+
+/// Largest link file that is read; shortcuts are a few hundred bytes.
+const MAX_LINK_FILE_BYTES: u64 = 64 * 1024;
+
+/// Kinds of file that only point at a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkFileKind {
+    /// Windows Internet Shortcut (`.url`), an INI file.
+    InternetShortcut,
+    /// macOS `.webloc`, a property list.
+    Webloc,
+    /// freedesktop `.desktop` entry of `Type=Link`.
+    DesktopLink,
+}
+
+impl LinkFileKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "url" => Some(LinkFileKind::InternetShortcut),
+            "webloc" => Some(LinkFileKind::Webloc),
+            "desktop" => Some(LinkFileKind::DesktopLink),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the URL a `.url`, `.webloc` or `.desktop` link file points at.
+///
+/// Returns `None` for other files, files that cannot be read and files without a URL.
+pub fn read_link_file(path: &Path) -> Option<String> {
+    let kind = LinkFileKind::from_path(path)?;
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_LINK_FILE_BYTES {
+        return None;
+    }
+    let contents = std::fs::read(path).ok()?;
+    parse_link_file(kind, &contents)
+}
+
+/// Extracts the target of a link file, which the caller still has to validate as a URL.
+pub fn parse_link_file(kind: LinkFileKind, contents: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(contents);
+    let text = text.trim_start_matches('\u{feff}');
+    match kind {
+        LinkFileKind::InternetShortcut => ini_value(text, "InternetShortcut", "URL"),
+        LinkFileKind::DesktopLink => {
+            let is_link = ini_value(text, "Desktop Entry", "Type")
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("Link"));
+            if is_link {
+                ini_value(text, "Desktop Entry", "URL")
+            } else {
+                None
+            }
+        }
+        // Binary property lists store the URL as a plain string, directly after a length
+        // byte that may be a letter, so scanning starts at the scheme itself.
+        LinkFileKind::Webloc => plist_url(text).or_else(|| {
+            let printable = text.replace(|c: char| c.is_control(), " ");
+            let start = printable.find("http")?;
+            extract_text_urls(&printable[start..], &WEB_SCHEMES)
+                .into_iter()
+                .next()
+        }),
+    }
+}
+
+/// Reads `key` from `[section]`, ignoring case and locale or `$e` suffixes such as `URL[$e]`.
+fn ini_value(text: &str, section: &str, key: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name.trim().eq_ignore_ascii_case(section);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.split('[').next().unwrap_or_default().trim();
+        let value = value.trim();
+        if name.eq_ignore_ascii_case(key) && !value.is_empty() {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+/// Reads the string following `<key>URL</key>` in an XML property list.
+fn plist_url(text: &str) -> Option<String> {
+    let after_key = &text[text.find("<key>URL</key>")? + "<key>URL</key>".len()..];
+    let start = after_key.find("<string>")? + "<string>".len();
+    let end = after_key[start..].find("</string>")? + start;
+    let value = after_key[start..end]
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some(value).filter(|value| !value.is_empty())
+}
@@ -0,0 +1,38 @@
+use super::files::LinkFileKind;
+use std::path::Path;
+use url::Url;
+
+/// Turns a local file argument into a `file://` URL, resolving relative paths against `cwd`.
+///
+/// Accepts `file:` URLs, including relative ones such as `file:build/index.html`, and paths
+/// to existing files. Link files are left to [`super::read_link_file`].
+pub fn local_file_url(raw: &str, cwd: &Path) -> Option<String> {
+    let trimmed = raw.trim().trim_matches(|c| matches!(c, '"' | '\''));
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let is_file_url = trimmed
+        .get(..5)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("file:"));
+    if is_file_url {
+        let rest = &trimmed[5..];
+        if rest.starts_with('/') {
+            return Url::parse(trimmed).ok().map(String::from);
+        }
+        let decoded = percent_encoding::percent_decode_str(rest)
+            .decode_utf8()
+            .ok()?;
+        let path = cwd.join(decoded.as_ref());
+        return Url::from_file_path(std::fs::canonicalize(&path).unwrap_or(path))
+            .ok()
+            .map(String::from);
+    }
+
+    let path = cwd.join(trimmed);
+    if LinkFileKind::from_path(&path).is_some() || !path.is_file() {
+        return None;
+    }
+    let path = std::fs::canonicalize(&path).ok()?;
+    Url::from_file_path(path).ok().map(String::from)
+}
@@ -0,0 +1,262 @@
+mod files;
+mod local;
+mod text;
+
+pub use files::{parse_link_file, read_link_file, LinkFileKind};
+pub use local::local_file_url;
+pub use text::extract_text_urls;
+
+use crate::preferences::PreferencesState;
+use crate::routing::{IncomingLink, RoutingService};
+use crate::schemes::{self, WEB_SCHEMES};
+use chrono::Utc;
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+#[derive(Debug, Clone, Copy)]
+pub enum LinkSource {
+    InitialLaunch,
+    SecondaryInstance,
+    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    OsEvent,
+}
+
+impl LinkSource {
+    fn source_app(self) -> &'static str {
+        match self {
+            LinkSource::InitialLaunch => "System",
+            LinkSource::SecondaryInstance => "System (handoff)",
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            LinkSource::OsEvent => "Operating System",
+        }
+    }
+
+    fn source_context(self) -> &'static str {
+        match self {
+            LinkSource::InitialLaunch => "App launch arguments",
+            LinkSource::SecondaryInstance => "Secondary instance activation",
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            LinkSource::OsEvent => "OS open-url event",
+        }
+    }
+}
+
+/// Routes the links in `args`, resolving relative file paths against `cwd`.
+pub fn handle_cli_arguments(app: &AppHandle, args: &[String], origin: LinkSource, cwd: &Path) {
+    let handle = app.clone();
+    let args = args.to_vec();
+    let cwd = cwd.to_path_buf();
+
+    tauri::async_runtime::spawn(async move {
+        let schemes = handled_schemes(&handle).await;
+        let mut urls = extract_urls_in(&args, &schemes, &cwd);
+
+        // Only the first instance can read stdin; a second one just forwards its arguments.
+        if matches!(origin, LinkSource::InitialLaunch) && args.iter().any(|arg| arg == "-") {
+            let stdin = tauri::async_runtime::spawn_blocking(read_stdin)
+                .await
+                .map_err(|err| err.to_string())
+                .and_then(|result| result);
+            match stdin {
+                Ok(text) => {
+                    for url in extract_text_urls(&text, &schemes) {
+                        push_unique(&mut urls, url);
+                    }
+                }
+                Err(err) => eprintln!("failed to read links from stdin: {err}"),
+            }
+        }
+
+        route_urls(&handle, urls, origin).await;
+    });
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut text = String::new();
+    std::io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|err| err.to_string())?;
+    Ok(text)
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn handle_open_urls(app: &AppHandle, urls: &[String], origin: LinkSource) {
+    let handle = app.clone();
+    let urls = urls.to_vec();
+
+    tauri::async_runtime::spawn(async move {
+        let schemes = handled_schemes(&handle).await;
+        let cleaned = urls
+            .iter()
+            .filter_map(|s| {
+                local_file_url(s, Path::new("/")).or_else(|| parse_candidate_for(s, &schemes))
+            })
+            .collect::<Vec<_>>();
+        route_urls(&handle, cleaned, origin).await;
+    });
+}
+
+async fn handled_schemes(app: &AppHandle) -> Vec<String> {
+    match app.try_state::<PreferencesState>() {
+        Some(state) => state.handled_schemes().await,
+        None => schemes::handled_schemes(&[]),
+    }
+}
+
+async fn route_urls(handle: &AppHandle, urls: Vec<String>, origin: LinkSource) {
+    let routing = handle.state::<RoutingService>();
+
+    for url in urls {
+        let mut link = IncomingLink {
+            id: String::new(),
+            url: url.clone(),
+            original_url: None,
+            source_app: origin.source_app().to_string(),
+            source_context: Some(origin.source_context().to_string()),
+            contact_name: None,
+            preview: None,
+            recommended_browser: None,
+            arrived_at: Some(Utc::now().to_rfc3339()),
+            warning: None,
+        };
+
+        if link.source_context.as_deref() == Some("") {
+            link.source_context = None;
+        }
+
+        if let Err(err) = routing.register_incoming(handle, link).await {
+            eprintln!("failed to register incoming link '{url}': {err}");
+        }
+    }
+}
+
+/// Extracts unique http(s) URLs from command-line arguments.
+pub fn extract_urls(args: &[String]) -> Vec<String> {
+    extract_urls_for(args, &WEB_SCHEMES)
+}
+
+/// Extracts unique URLs with one of `schemes` from command-line arguments.
+///
+/// Besides plain URLs this reads `.url`, `.webloc` and `.desktop` link files and finds URLs
+/// inside free-text arguments. A lone `-` (read stdin) is left to the caller.
+pub fn extract_urls_for<S: AsRef<str>>(args: &[String], schemes: &[S]) -> Vec<String> {
+    collect_urls(args, schemes, None)
+}
+
+/// Like [`extract_urls_for`], but also opens local files and `file:` URLs, resolving
+/// relative paths against `cwd`, the working directory of the process that passed them.
+pub fn extract_urls_in<S: AsRef<str>>(args: &[String], schemes: &[S], cwd: &Path) -> Vec<String> {
+    collect_urls(args, schemes, Some(cwd))
+}
+
+fn collect_urls<S: AsRef<str>>(args: &[String], schemes: &[S], cwd: Option<&Path>) -> Vec<String> {
+    let mut collected = Vec::new();
+    let mut after_delimiter = false;
+
+    for raw in args {
+        if raw == "--" {
+            after_delimiter = true;
+            continue;
+        }
+
+        let path = cwd.map_or_else(|| PathBuf::from(raw), |cwd| cwd.join(raw));
+        let linked = read_link_file(&path).and_then(|target| parse_candidate_for(&target, schemes));
+        if let Some(parsed) = linked.or_else(|| local_file_url(raw, cwd?)) {
+            push_unique(&mut collected, parsed);
+            continue;
+        }
+
+        if let Some(parsed) = parse_argument_for(raw, schemes) {
+            push_unique(&mut collected, parsed);
+            continue;
+        }
+
+        if after_delimiter || !raw.starts_with('-') {
+            for parsed in extract_text_urls(raw, schemes) {
+                push_unique(&mut collected, parsed);
+            }
+        } else if let Some(idx) = raw.find('=') {
+            let value = &raw[idx + 1..];
+            if let Some(parsed) = parse_candidate_for(value, schemes) {
+                push_unique(&mut collected, parsed);
+            }
+        }
+    }
+
+    collected
+}
+
+/// Parses an individual CLI argument for a url candidate.
+pub fn parse_argument(arg: &str) -> Option<String> {
+    parse_argument_for(arg, &WEB_SCHEMES)
+}
+
+/// Parses an individual CLI argument for a url candidate with one of `schemes`.
+pub fn parse_argument_for<S: AsRef<str>>(arg: &str, schemes: &[S]) -> Option<String> {
+    if let Some(candidate) = parse_candidate_for(arg, schemes) {
+        return Some(candidate);
+    }
+
+    // Handle flags such as --url=https://example.com
+    if let Some(stripped) = arg.strip_prefix("--url=") {
+        return parse_candidate_for(stripped, schemes);
+    }
+
+    if let Some(stripped) = arg.strip_prefix("url=") {
+        return parse_candidate_for(stripped, schemes);
+    }
+
+    None
+}
+
+/// Parses a raw string, returning a normalized http(s) URL if valid.
+pub fn parse_candidate(input: &str) -> Option<String> {
+    parse_candidate_for(input, &WEB_SCHEMES)
+}
+
+/// Parses a raw string, returning a normalized URL if its scheme is one of `schemes`.
+pub fn parse_candidate_for<S: AsRef<str>>(input: &str, schemes: &[S]) -> Option<String> {
+    let trimmed = input.trim_matches(|c| matches!(c, '"' | '\''));
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    // Handle surrounding angle brackets that some launchers add.
+    let trimmed = trimmed
+        .strip_prefix('<')
+        .and_then(|s| s.strip_suffix('>'))
+        .unwrap_or(trimmed);
+
+    let decoded = percent_decode_if_needed(trimmed);
+
+    if let Ok(url) = Url::parse(&decoded) {
+        if schemes
+            .iter()
+            .any(|scheme| scheme.as_ref().eq_ignore_ascii_case(url.scheme()))
+        {
+            return Some(url.to_string());
+        }
+    }
+
+    None
+}
+
+/// Percent decodes a string when common URL encodings are present.
+pub fn percent_decode_if_needed(input: &str) -> Cow<'_, str> {
+    if input.contains("%3A") || input.contains("%2F") {
+        if let Ok(decoded) = percent_encoding::percent_decode_str(input).decode_utf8() {
+            return Cow::Owned(decoded.into_owned());
+        }
+    }
+    Cow::Borrowed(input)
+}
+
+/// Pushes a value into the list when it does not already exist.
+pub fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.iter().any(|existing| existing == &value) {
+        list.push(value);
+    }
+}
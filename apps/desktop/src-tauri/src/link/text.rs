@@ -0,0 +1,109 @@
+use super::{parse_candidate_for, push_unique};
+
+/// Characters that end a URL in running text.
+fn is_terminator(c: char) -> bool {
+    c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | '"' | '`' | '|' | '\\')
+}
+
+/// Punctuation that belongs to the sentence rather than the URL when it trails one.
+const TRAILING_PUNCTUATION: [char; 11] = ['.', ',', ';', ':', '!', '?', '\'', '*', '_', '~', '"'];
+
+/// Finds URLs with one of `schemes` in free text such as chat messages or markdown.
+///
+/// Angle brackets, markdown link syntax, unbalanced closing brackets and trailing sentence
+/// punctuation are not taken as part of a URL. `www.` hosts without a scheme become https.
+pub fn extract_text_urls<S: AsRef<str>>(text: &str, schemes: &[S]) -> Vec<String> {
+    let mut collected = Vec::new();
+    let prefixes = url_prefixes(schemes);
+    let lowered = text.to_ascii_lowercase();
+    let mut position = 0;
+
+    while position < text.len() {
+        let Some((start, prefix)) = next_prefix(&lowered, position, &prefixes) else {
+            break;
+        };
+        let end = text[start..]
+            .find(is_terminator)
+            .map_or(text.len(), |offset| start + offset);
+        let candidate = trim_candidate(&text[start..end]);
+
+        if candidate.len() > prefix.len() {
+            let candidate = if prefix == "www." {
+                format!("https://{candidate}")
+            } else {
+                candidate.to_string()
+            };
+            if let Some(url) = parse_candidate_for(&candidate, schemes) {
+                push_unique(&mut collected, url);
+            }
+        }
+        position = end.max(start + prefix.len());
+    }
+
+    collected
+}
+
+/// The text that starts a URL for each scheme, plus `www.` when https is accepted.
+fn url_prefixes<S: AsRef<str>>(schemes: &[S]) -> Vec<String> {
+    let mut prefixes = schemes
+        .iter()
+        .map(|scheme| {
+            let scheme = scheme.as_ref().to_ascii_lowercase();
+            if matches!(scheme.as_str(), "http" | "https" | "ftp" | "file") {
+                format!("{scheme}://")
+            } else {
+                format!("{scheme}:")
+            }
+        })
+        .collect::<Vec<_>>();
+    if prefixes.iter().any(|prefix| prefix == "https://") {
+        prefixes.push("www.".to_string());
+    }
+    prefixes
+}
+
+/// Locates the earliest prefix at or after `from` that starts a word.
+fn next_prefix<'a>(lowered: &str, from: usize, prefixes: &'a [String]) -> Option<(usize, &'a str)> {
+    prefixes
+        .iter()
+        .filter_map(|prefix| {
+            let mut search = from;
+            while let Some(offset) = lowered[search..].find(prefix.as_str()) {
+                let start = search + offset;
+                let boundary = lowered[..start]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !(c.is_alphanumeric() || matches!(c, '+' | '-' | '.' | '/')));
+                if boundary {
+                    return Some((start, prefix.as_str()));
+                }
+                search = start + prefix.len();
+            }
+            None
+        })
+        .min_by_key(|(start, _)| *start)
+}
+
+/// Cuts a candidate at its first unbalanced closing bracket and drops trailing punctuation.
+fn trim_candidate(candidate: &str) -> &str {
+    let mut depth = [0usize; 3];
+    let mut end = candidate.len();
+    for (index, c) in candidate.char_indices() {
+        let slot = match c {
+            '(' | ')' => 0,
+            '[' | ']' => 1,
+            '{' | '}' => 2,
+            _ => continue,
+        };
+        if matches!(c, '(' | '[' | '{') {
+            depth[slot] += 1;
+        } else if depth[slot] == 0 {
+            end = index;
+            break;
+        } else {
+            depth[slot] -= 1;
+        }
+    }
+
+    candidate[..end].trim_end_matches(TRAILING_PUNCTUATION)
+}
@@ -1,12 +1,14 @@
+use crate::desktop_entries::OWN_DESKTOP_FILE as DESKTOP_FILE_NAME;
 use std::fs;
 use std::io;
 use std::path::PathBuf;
 use std::process::Command;
 use tauri::AppHandle;
 
-const DESKTOP_FILE_NAME: &str = "open-with-browser.desktop";
+/// Local documents the router can open, like a browser does, without claiming them by default.
+const DOCUMENT_MIME_TYPES: [&str; 2] = ["text/html", "application/xhtml+xml"];
 
-pub fn register(_app: &AppHandle) -> Result<(), String> {
+pub fn register(_app: &AppHandle, schemes: &[String]) -> Result<(), String> {
     let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
     let exe_str = exe_path
         .to_str()
@@ -15,29 +17,40 @@ pub fn register(_app: &AppHandle) -> Result<(), String> {
     let applications_dir = resolve_applications_dir()?;
     fs::create_dir_all(&applications_dir).map_err(map_fs_error)?;
 
+    let scheme_types = schemes
+        .iter()
+        .map(|scheme| format!("x-scheme-handler/{scheme}"))
+        .collect::<Vec<_>>();
+    let mime_line = scheme_types
+        .iter()
+        .map(String::as_str)
+        .chain(DOCUMENT_MIME_TYPES)
+        .map(|mime| format!("{mime};"))
+        .collect::<String>();
+
     let desktop_path = applications_dir.join(DESKTOP_FILE_NAME);
     let desktop_entry = format!(
         "[Desktop Entry]\n\
 Version=1.0\n\
 Type=Application\n\
 Name=Open With Browser\n\
-Comment=Route http and https links through Open With Browser\n\
+Comment=Route links through Open With Browser\n\
 Exec=\"{exe_str}\" %u\n\
 Terminal=false\n\
 Categories=Network;WebBrowser;\n\
-MimeType=x-scheme-handler/http;x-scheme-handler/https;\n\
+MimeType={mime_line}\n\
 ",
     );
 
     fs::write(&desktop_path, desktop_entry).map_err(map_fs_error)?;
 
+    // Documents are only listed, so opening a local HTML file keeps its current default app.
     if which::which("xdg-mime").is_ok() {
-        let _ = Command::new("xdg-mime")
-            .args(["default", DESKTOP_FILE_NAME, "x-scheme-handler/http"])
-            .status();
-        let _ = Command::new("xdg-mime")
-            .args(["default", DESKTOP_FILE_NAME, "x-scheme-handler/https"])
-            .status();
+        for mime in &scheme_types {
+            let _ = Command::new("xdg-mime")
+                .args(["default", DESKTOP_FILE_NAME, mime])
+                .status();
+        }
     }
 
     if which::which("update-desktop-database").is_ok() {
@@ -8,14 +8,23 @@ mod windows;
 use tauri::AppHandle;
 use tauri_plugin_os::OsType;
 
-pub fn register_as_browser(app: &AppHandle) -> Result<(), String> {
+/// Registers the app as the handler for `schemes`, which should include http and https.
+pub fn register_as_browser(app: &AppHandle, schemes: &[String]) -> Result<(), String> {
     match tauri_plugin_os::type_() {
         #[cfg(target_os = "windows")]
-        OsType::Windows => windows::register(app),
+        OsType::Windows => windows::register(app, schemes),
+        // Info.plist declares http and https, the only schemes macOS lets the app claim.
         #[cfg(target_os = "macos")]
         OsType::Macos => macos::register(app),
         #[cfg(target_os = "linux")]
-        OsType::Linux => linux::register(app),
+        OsType::Linux => linux::register(app, schemes),
         _ => Ok(()),
     }
 }
+
+/// Whether schemes beyond http(s) can be claimed at runtime.
+///
+/// macOS only routes the URL types declared in the bundle's Info.plist to the app.
+pub fn claims_custom_schemes() -> bool {
+    !matches!(tauri_plugin_os::type_(), OsType::Macos)
+}
@@ -8,7 +8,7 @@ const CLIENT_KEY_PATH: &str = "Software\\Clients\\StartMenuInternet\\OpenWithBro
 const REGISTERED_APPLICATIONS_KEY: &str = "Software\\RegisteredApplications";
 const PROTOCOL_CLASS_KEY: &str = "Software\\Classes\\OpenWithBrowserURL";
 
-pub fn register(_app: &AppHandle) -> Result<(), String> {
+pub fn register(_app: &AppHandle, schemes: &[String]) -> Result<(), String> {
     let exe_path = std::env::current_exe().map_err(|e| e.to_string())?;
     let exe_str = exe_path
         .to_str()
@@ -51,7 +51,7 @@ pub fn register(_app: &AppHandle) -> Result<(), String> {
     capabilities
         .set_value(
             "ApplicationDescription",
-            &"Route http and https links through Open With Browser",
+            &"Route links through Open With Browser",
         )
         .map_err(map_registry_error)?;
     let (start_menu, _) = capabilities
@@ -64,12 +64,23 @@ pub fn register(_app: &AppHandle) -> Result<(), String> {
     let (url_associations, _) = capabilities
         .create_subkey("URLAssociations")
         .map_err(map_registry_error)?;
-    url_associations
-        .set_value("http", &"OpenWithBrowserURL")
-        .map_err(map_registry_error)?;
-    url_associations
-        .set_value("https", &"OpenWithBrowserURL")
-        .map_err(map_registry_error)?;
+    // Schemes the user stopped handling would otherwise stay claimed.
+    let stale = url_associations
+        .enum_values()
+        .filter_map(Result::ok)
+        .map(|(name, _)| name)
+        .filter(|name| !schemes.contains(name))
+        .collect::<Vec<_>>();
+    for name in stale {
+        url_associations
+            .delete_value(&name)
+            .map_err(map_registry_error)?;
+    }
+    for scheme in schemes {
+        url_associations
+            .set_value(scheme, &"OpenWithBrowserURL")
+            .map_err(map_registry_error)?;
+    }
 
     // Register application capabilities for Settings UI
     let (registered_apps, _) = hkcu
@@ -1,3 +1,5 @@
+use crate::cleaning::{ShortLinkExpansion, TrackingCleanup};
+use crate::schemes::{self, SchemeHandler};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::io::ErrorKind;
@@ -11,11 +13,55 @@ const PREFERENCES_KEY: &str = "preferences";
 pub struct Preferences {
     #[serde(default)]
     pub fallback: Option<FallbackPreference>,
+    /// Names of the rule sets that can be switched between.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_workspace: Option<String>,
+    /// Tracking parameters stripped from links before they are opened.
+    #[serde(default)]
+    pub tracking: TrackingCleanup,
+    /// Which short links are expanded before rules are evaluated.
+    #[serde(default)]
+    pub short_links: ShortLinkExpansion,
+    /// Schemes beyond http(s) that links are accepted for, with their default browsers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schemes: Vec<SchemeHandler>,
+}
+
+impl Preferences {
+    /// Trims and de-duplicates workspace names, dropping an active workspace that no longer exists.
+    pub fn normalize_workspaces(&mut self) {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.workspaces.drain(..) {
+            let name = name.trim().to_string();
+            if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                names.push(name);
+            }
+        }
+        self.workspaces = names;
+
+        self.active_workspace = self
+            .active_workspace
+            .take()
+            .and_then(|active| self.workspace_named(&active).map(str::to_string));
+    }
+
+    /// Looks up a defined workspace, ignoring case and surrounding whitespace.
+    pub fn workspace_named(&self, name: &str) -> Option<&str> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.eq_ignore_ascii_case(name.trim()))
+            .map(String::as_str)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FallbackPreference {
     pub browser: String,
+    /// Stable id of the browser, which keeps the preference working when it is renamed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_id: Option<String>,
     #[serde(default)]
     pub profile: Option<ProfilePreference>,
 }
@@ -40,6 +86,11 @@ impl PreferencesState {
         })
     }
 
+    pub async fn snapshot(&self) -> Preferences {
+        let guard = self.inner.read().await;
+        guard.clone()
+    }
+
     pub async fn fallback(&self) -> Option<FallbackPreference> {
         let guard = self.inner.read().await;
         guard.fallback.clone()
@@ -57,6 +108,139 @@ impl PreferencesState {
 
         persist_preferences(app, &self.inner).await
     }
+
+    pub async fn active_workspace(&self) -> Option<String> {
+        let guard = self.inner.read().await;
+        guard.active_workspace.clone()
+    }
+
+    pub async fn tracking_cleanup(&self) -> TrackingCleanup {
+        let guard = self.inner.read().await;
+        guard.tracking.clone()
+    }
+
+    /// Updates the tracking-parameter settings; `None` restores the built-in list.
+    pub async fn set_tracking_cleanup(
+        &self,
+        app: &AppHandle,
+        cleanup: Option<TrackingCleanup>,
+    ) -> Result<TrackingCleanup, String> {
+        let cleanup = cleanup.unwrap_or_default().normalized();
+        {
+            let mut guard = self.inner.write().await;
+            guard.tracking = cleanup.clone();
+        }
+
+        persist_preferences(app, &self.inner).await?;
+        Ok(cleanup)
+    }
+
+    pub async fn short_link_expansion(&self) -> ShortLinkExpansion {
+        let guard = self.inner.read().await;
+        guard.short_links.clone()
+    }
+
+    /// Updates short-link expansion; `None` restores the defaults, which leave it off.
+    pub async fn set_short_link_expansion(
+        &self,
+        app: &AppHandle,
+        settings: Option<ShortLinkExpansion>,
+    ) -> Result<ShortLinkExpansion, String> {
+        let settings = settings.unwrap_or_default().normalized();
+        {
+            let mut guard = self.inner.write().await;
+            guard.short_links = settings.clone();
+        }
+
+        persist_preferences(app, &self.inner).await?;
+        Ok(settings)
+    }
+
+    pub async fn scheme_handlers(&self) -> Vec<SchemeHandler> {
+        let guard = self.inner.read().await;
+        guard.schemes.clone()
+    }
+
+    /// Every scheme links are accepted for, http(s) included.
+    pub async fn handled_schemes(&self) -> Vec<String> {
+        let guard = self.inner.read().await;
+        schemes::handled_schemes(&guard.schemes)
+    }
+
+    /// Replaces the schemes handled beyond http(s), rejecting invalid or unsafe ones.
+    pub async fn set_scheme_handlers(
+        &self,
+        app: &AppHandle,
+        handlers: Vec<SchemeHandler>,
+    ) -> Result<Vec<SchemeHandler>, String> {
+        let handlers = schemes::normalize_handlers(handlers)?;
+        {
+            let mut guard = self.inner.write().await;
+            guard.schemes = handlers.clone();
+        }
+
+        persist_preferences(app, &self.inner).await?;
+        Ok(handlers)
+    }
+
+    /// Replaces the list of workspaces, clearing the active one if it was removed.
+    pub async fn set_workspaces(
+        &self,
+        app: &AppHandle,
+        workspaces: Vec<String>,
+    ) -> Result<Preferences, String> {
+        let preferences = {
+            let mut guard = self.inner.write().await;
+            guard.workspaces = workspaces;
+            guard.normalize_workspaces();
+            guard.clone()
+        };
+
+        persist_preferences(app, &self.inner).await?;
+        Ok(preferences)
+    }
+
+    /// Activates a defined workspace by name, or none to use only the shared rules.
+    pub async fn set_active_workspace(
+        &self,
+        app: &AppHandle,
+        workspace: Option<String>,
+    ) -> Result<Option<String>, String> {
+        let active = {
+            let mut guard = self.inner.write().await;
+            let active = match workspace.as_deref().map(str::trim) {
+                None | Some("") => None,
+                Some(name) => Some(
+                    guard
+                        .workspace_named(name)
+                        .ok_or_else(|| format!("No workspace named '{name}' exists."))?
+                        .to_string(),
+                ),
+            };
+            guard.active_workspace = active.clone();
+            active
+        };
+
+        persist_preferences(app, &self.inner).await?;
+        Ok(active)
+    }
+
+    pub async fn replace(
+        &self,
+        app: &AppHandle,
+        mut preferences: Preferences,
+    ) -> Result<(), String> {
+        preferences.normalize_workspaces();
+        preferences.tracking = preferences.tracking.normalized();
+        preferences.short_links = preferences.short_links.normalized();
+        preferences.schemes = schemes::normalize_handlers(preferences.schemes)?;
+        {
+            let mut guard = self.inner.write().await;
+            *guard = preferences;
+        }
+
+        persist_preferences(app, &self.inner).await
+    }
 }
 
 fn load_preferences(app: &AppHandle) -> Result<Preferences, String> {
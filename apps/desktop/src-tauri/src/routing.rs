@@ -1,6 +1,16 @@
-use crate::preferences::PreferencesState;
-use chrono::Utc;
-use crowser::browser::{get_all_existing_browsers, get_browser_path};
+use crate::browser_descriptor::BrowserDescriptor;
+use crate::browser_details::{
+    browser_catalog, browser_name_for_id, resolve_browser_id, BrowserCatalog,
+};
+use crate::cleaning::{strip_tracking_parameters, unwrap_redirects, ShortLinkCacheState};
+use crate::domain::models::{Rule, RulePolicy};
+use crate::homograph::{check_host, LinkWarning};
+use crate::preferences::{FallbackPreference, PreferencesState};
+use crate::rules::{
+    explain_rules, rewrite_url, LinkFacts, RuleEvaluation, RuleStatsState, RulesState,
+};
+use crate::schemes;
+use chrono::{Local, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::Command;
@@ -19,8 +29,9 @@ use dirs::home_dir;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+/// Browser and profile suggested for a link by whoever sent it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BrowserDescriptor {
+pub struct RecommendedBrowser {
     pub name: String,
     #[serde(default)]
     pub profile_label: Option<String>,
@@ -32,6 +43,9 @@ pub struct BrowserDescriptor {
 pub struct IncomingLink {
     pub id: String,
     pub url: String,
+    /// The link as it arrived, when it was unwrapped or expanded from a short link.
+    #[serde(default)]
+    pub original_url: Option<String>,
     pub source_app: String,
     #[serde(default)]
     pub source_context: Option<String>,
@@ -40,15 +54,28 @@ pub struct IncomingLink {
     #[serde(default)]
     pub preview: Option<String>,
     #[serde(default)]
-    pub recommended_browser: Option<BrowserDescriptor>,
+    pub recommended_browser: Option<RecommendedBrowser>,
     #[serde(default)]
     pub arrived_at: Option<String>,
+    /// Set when the host looks like it imitates another site; such links are never
+    /// sent to the fallback browser without asking.
+    #[serde(default)]
+    pub warning: Option<LinkWarning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LaunchDecision {
     pub id: String,
     pub url: String,
+    /// The link as it arrived, when it was unwrapped, rewritten by a rule or stripped of tracking.
+    #[serde(default)]
+    pub original_url: Option<String>,
+    /// The rule that made this decision, if it was not picked by hand or by the fallback.
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// The URL that rule was evaluated against, after unwrapping and expansion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_url: Option<String>,
     pub browser: String,
     #[serde(default)]
     pub profile_label: Option<String>,
@@ -62,23 +89,20 @@ pub struct LaunchDecision {
     pub contact_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum PersistChoice {
+    #[default]
     JustOnce,
     Always,
 }
 
-impl Default for PersistChoice {
-    fn default() -> Self {
-        PersistChoice::JustOnce
-    }
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct RoutingSnapshot {
     pub active: Option<IncomingLink>,
     pub history: Vec<LaunchDecision>,
+    /// Workspace whose rules are currently applied.
+    pub active_workspace: Option<String>,
 }
 
 #[derive(Clone)]
@@ -104,6 +128,7 @@ impl RoutingService {
         RoutingSnapshot {
             active: guard.active.clone(),
             history: guard.history.clone(),
+            active_workspace: None,
         }
     }
 
@@ -123,6 +148,35 @@ impl RoutingService {
                 link.id, link.url, link.source_app
             ),
         );
+        if let Some(unwrapped) = unwrap_redirects(&link.url) {
+            append_log(
+                app_handle,
+                &format!(
+                    "Unwrapped redirect link for id={}: {} -> {}",
+                    link.id, link.url, unwrapped
+                ),
+            );
+            link.original_url.get_or_insert_with(|| link.url.clone());
+            link.url = unwrapped;
+        }
+        if let Some(expanded) = expand_link(app_handle, &link.url).await {
+            append_log(
+                app_handle,
+                &format!(
+                    "Expanded short link for id={}: {} -> {}",
+                    link.id, link.url, expanded
+                ),
+            );
+            link.original_url.get_or_insert_with(|| link.url.clone());
+            link.url = expanded;
+        }
+        link.warning = Url::parse(&link.url).ok().and_then(|url| check_host(&url));
+        if let Some(warning) = &link.warning {
+            append_log(
+                app_handle,
+                &format!("Suspicious host for id={}: {}", link.id, warning.message),
+            );
+        }
         {
             let mut guard = self.inner.write().await;
             guard.active = Some(link.clone());
@@ -131,37 +185,42 @@ impl RoutingService {
             .emit("routing://incoming", link.clone())
             .map_err(|e| e.to_string())?;
 
-        if let Some(prefs) = app_handle.try_state::<PreferencesState>() {
-            if let Some(fallback) = prefs.fallback().await {
-                let profile_label = fallback
-                    .profile
-                    .as_ref()
-                    .and_then(|p| p.label.clone())
-                    .filter(|s| !s.is_empty());
-                let profile_directory = fallback
-                    .profile
-                    .as_ref()
-                    .and_then(|p| p.directory.clone())
-                    .filter(|s| !s.is_empty());
-                let decision = LaunchDecision {
-                    id: link.id.clone(),
-                    url: link.url.clone(),
-                    browser: fallback.browser.clone(),
-                    profile_label,
-                    profile_directory,
-                    persist: PersistChoice::Always,
-                    decided_at: None,
-                    source_app: link.source_app.clone(),
-                    contact_name: link.contact_name.clone(),
-                };
+        let rule = match LinkFacts::from_url(&link.url) {
+            Some(facts) => find_rule(app_handle, &link_facts(facts, &link)).await,
+            None => None,
+        };
+        if let Some(rule) = &rule {
+            append_log(
+                app_handle,
+                &format!(
+                    "Rule matched for id={}: rule_id={} pattern={} policy={:?}",
+                    link.id, rule.id, rule.rule_name, rule.action.policy
+                ),
+            );
+        }
 
-                if let Err(err) = self.resolve(app_handle, decision).await {
-                    eprintln!("automatic fallback failed: {err}");
-                    append_log(
-                        app_handle,
-                        &format!("Automatic fallback failed for link id={}: {}", link.id, err),
-                    );
-                }
+        let plan = plan_route(app_handle, &link, rule.as_ref()).await;
+        if let Some(note) = &plan.note {
+            append_log(app_handle, note);
+        }
+        // Unwrapping and expansion already changed `link.url`; only a rule rewrite differs here.
+        if let Some(decision) = plan.decision.as_ref().filter(|d| d.url != link.url) {
+            append_log(
+                app_handle,
+                &format!(
+                    "Rewrote URL for id={}: {} -> {}",
+                    link.id, link.url, decision.url
+                ),
+            );
+        }
+
+        if let Some(decision) = plan.decision {
+            if let Err(err) = self.resolve(app_handle, decision).await {
+                eprintln!("automatic routing failed: {err}");
+                append_log(
+                    app_handle,
+                    &format!("Automatic routing failed for link id={}: {}", link.id, err),
+                );
             }
         }
 
@@ -186,6 +245,24 @@ impl RoutingService {
             return Err("Link does not contain a valid URL to open.".to_string());
         }
 
+        if let Some(preferences) = app_handle.try_state::<PreferencesState>() {
+            let cleanup = preferences.tracking_cleanup().await;
+            if let Some((cleaned, removed)) = strip_tracking_parameters(&decision.url, &cleanup) {
+                append_log(
+                    app_handle,
+                    &format!(
+                        "Stripped tracking parameters for id={}: {}",
+                        decision.id,
+                        removed.join(", ")
+                    ),
+                );
+                decision
+                    .original_url
+                    .get_or_insert_with(|| decision.url.clone());
+                decision.url = cleaned;
+            }
+        }
+
         if decision.decided_at.is_none() {
             decision.decided_at = Some(current_timestamp());
         }
@@ -197,6 +274,19 @@ impl RoutingService {
             guard.history.truncate(50);
         }
 
+        if let Some(rule_id) = &decision.rule_id {
+            if let Some(stats) = app_handle.try_state::<RuleStatsState>() {
+                let url = decision.matched_url.as_deref().unwrap_or(&decision.url);
+                let matched_at = decision.decided_at.as_deref().unwrap_or_default();
+                if let Err(err) = stats.record(app_handle, rule_id, url, matched_at).await {
+                    append_log(
+                        app_handle,
+                        &format!("Failed to record hit for rule_id={rule_id}: {err}"),
+                    );
+                }
+            }
+        }
+
         app_handle
             .emit("routing://decision", decision.clone())
             .map_err(|e| e.to_string())?;
@@ -213,7 +303,9 @@ impl RoutingService {
                 },
             );
 
-            let Some(browser_path) = resolve_browser_path(&launch_event.browser) else {
+            let catalog = browser_catalog(&app).await;
+            let Some(browser_command) = resolve_browser_command(&catalog, &launch_event.browser)
+            else {
                 let message = format!(
                     "No executable found for browser '{}' while handling id={}.",
                     launch_event.browser, launch_event.id
@@ -238,7 +330,7 @@ impl RoutingService {
                 return;
             };
 
-            let path_display = browser_path.display().to_string();
+            let path_display = browser_command.program.display().to_string();
             let profile_info = match (
                 &launch_event.profile_label,
                 &launch_event.profile_directory,
@@ -269,7 +361,7 @@ impl RoutingService {
 
             let launch_result = async_runtime::spawn_blocking(move || {
                 launch_with_browser(
-                    browser_path,
+                    browser_command,
                     &browser_name,
                     &url_to_open,
                     profile_directory,
@@ -345,23 +437,433 @@ impl RoutingService {
     }
 }
 
+/// Where the decision for an incoming link comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RouteVia {
+    Rule,
+    /// The default browser configured for a non-web scheme such as `mailto`.
+    Scheme,
+    Fallback,
+    Picker,
+}
+
+/// What the router will do with a link, worked out before anything is launched.
+#[derive(Debug, Clone)]
+pub struct RoutePlan {
+    pub via: RouteVia,
+    pub decision: Option<LaunchDecision>,
+    pub note: Option<String>,
+}
+
+/// What a matched rule asks the router to do with an incoming link.
+enum RuleOutcome {
+    Launch(Box<LaunchDecision>, Option<String>),
+    Prompt,
+    Fallback(Option<String>),
+}
+
+fn link_facts(facts: LinkFacts, link: &IncomingLink) -> LinkFacts {
+    facts.with_source(&link.source_app, link.source_context.as_deref())
+}
+
+/// Expands a configured short link, then unwraps any redirect wrapper it pointed at.
+async fn expand_link(app_handle: &tauri::AppHandle, url: &str) -> Option<String> {
+    let settings = app_handle
+        .try_state::<PreferencesState>()?
+        .short_link_expansion()
+        .await;
+    let expanded = app_handle
+        .try_state::<ShortLinkCacheState>()?
+        .expand(app_handle, url, &settings)
+        .await?;
+    Some(unwrap_redirects(&expanded).unwrap_or(expanded))
+}
+
+/// Looks a short link up in the expansion cache without contacting the shortener.
+///
+/// `Some(None)` means `url` is a configured short link that has not been expanded yet.
+async fn cached_expansion(app_handle: &tauri::AppHandle, url: &str) -> Option<Option<String>> {
+    let settings = app_handle
+        .try_state::<PreferencesState>()?
+        .short_link_expansion()
+        .await;
+    let parsed = Url::parse(url).ok()?;
+    if !settings.enabled || !settings.is_short_link(&parsed) {
+        return None;
+    }
+    let cached = app_handle
+        .try_state::<ShortLinkCacheState>()?
+        .cached(url)
+        .await;
+    Some(cached.map(|expanded| unwrap_redirects(&expanded).unwrap_or(expanded)))
+}
+
+async fn find_rule(app_handle: &tauri::AppHandle, facts: &LinkFacts) -> Option<Rule> {
+    app_handle
+        .try_state::<RulesState>()?
+        .find_match(facts)
+        .await
+}
+
+/// Turns the matched rule, if any, into a launch decision or a hand-off to the picker.
+pub async fn plan_route(
+    app_handle: &tauri::AppHandle,
+    link: &IncomingLink,
+    rule: Option<&Rule>,
+) -> RoutePlan {
+    let browsers = browser_catalog(app_handle).await.descriptors;
+    let note = match rule.map(|rule| rule_outcome(link, rule, &browsers)) {
+        Some(RuleOutcome::Launch(decision, note)) => {
+            return RoutePlan {
+                via: RouteVia::Rule,
+                decision: Some(*decision),
+                note,
+            }
+        }
+        Some(RuleOutcome::Prompt) => {
+            return RoutePlan {
+                via: RouteVia::Picker,
+                decision: None,
+                note: None,
+            }
+        }
+        Some(RuleOutcome::Fallback(note)) => note,
+        None => None,
+    };
+
+    if let Ok(url) = Url::parse(&link.url) {
+        let scheme = url.scheme();
+        if !schemes::is_web_scheme(scheme) {
+            if let Some(decision) = scheme_decision(app_handle, link, scheme, &browsers).await {
+                return RoutePlan {
+                    via: RouteVia::Scheme,
+                    decision: Some(decision),
+                    note,
+                };
+            }
+            // Browsers hand schemes they do not render back to the system default, which
+            // would be this app again, so only local files may go to the fallback browser.
+            if !schemes::opens_in_browser(scheme) {
+                return RoutePlan {
+                    via: RouteVia::Picker,
+                    decision: None,
+                    note: note.or_else(|| {
+                        Some(format!(
+                            "No browser is set for {scheme}: links; the fallback is web-only."
+                        ))
+                    }),
+                };
+            }
+        }
+    }
+
+    match fallback_decision(app_handle, link, &browsers).await {
+        Some(_) if link.warning.is_some() => RoutePlan {
+            via: RouteVia::Picker,
+            decision: None,
+            note: Some(
+                "The fallback browser was skipped because the link's host looks suspicious."
+                    .to_string(),
+            ),
+        },
+        Some(decision) => RoutePlan {
+            via: RouteVia::Fallback,
+            decision: Some(decision),
+            note,
+        },
+        None => RoutePlan {
+            via: RouteVia::Picker,
+            decision: None,
+            note,
+        },
+    }
+}
+
+fn rule_outcome(link: &IncomingLink, rule: &Rule, browsers: &[BrowserDescriptor]) -> RuleOutcome {
+    if rule.action.policy == RulePolicy::Fallback {
+        return RuleOutcome::Fallback(None);
+    }
+
+    if rule.action.browser.is_empty() {
+        return RuleOutcome::Prompt;
+    }
+
+    let Some((browser, profile)) = resolve_browser_id(browsers, &rule.action.browser) else {
+        return RuleOutcome::Fallback(Some(format!(
+            "Rule {} targets unavailable browser '{}'; using fallback instead.",
+            rule.id, rule.action.browser
+        )));
+    };
+
+    let persist = match rule.action.policy {
+        RulePolicy::JustOnce => PersistChoice::JustOnce,
+        _ => PersistChoice::Always,
+    };
+
+    let rewritten = LinkFacts::from_url(&link.url)
+        .and_then(|facts| rewrite_url(rule, &link_facts(facts, link)));
+    let (url, note) = match rewritten {
+        Some(Ok(url)) => (url, None),
+        Some(Err(err)) => (
+            link.url.clone(),
+            Some(format!(
+                "Rule {} could not rewrite the URL: {err} Opening it unchanged.",
+                rule.id
+            )),
+        ),
+        None => (link.url.clone(), None),
+    };
+    let original_url = link
+        .original_url
+        .clone()
+        .or_else(|| (url != link.url).then(|| link.url.clone()));
+
+    let decision = LaunchDecision {
+        id: link.id.clone(),
+        url,
+        original_url,
+        rule_id: Some(rule.id.clone()),
+        matched_url: Some(link.url.clone()),
+        browser,
+        profile_label: profile.as_ref().map(|p| p.display_name.clone()),
+        profile_directory: profile.map(|p| p.directory),
+        persist,
+        decided_at: None,
+        source_app: link.source_app.clone(),
+        contact_name: link.contact_name.clone(),
+    };
+    RuleOutcome::Launch(Box::new(decision), note)
+}
+
+/// Dry-run report of how a URL would be routed.
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteExplanation {
+    pub input_url: String,
+    pub normalized_url: String,
+    pub source_app: String,
+    pub source_context: Option<String>,
+    /// Local time schedule conditions were evaluated at.
+    pub evaluated_at: String,
+    pub rules: Vec<RuleEvaluation>,
+    pub matched_rule_id: Option<String>,
+    pub via: RouteVia,
+    pub decision: Option<LaunchDecision>,
+    pub notes: Vec<String>,
+}
+
+/// Runs a URL through the routing pipeline without registering or launching it.
+pub async fn explain_route(
+    app_handle: &tauri::AppHandle,
+    url: &str,
+    source_app: Option<String>,
+    source_context: Option<String>,
+) -> RouteExplanation {
+    let mut link = IncomingLink {
+        id: Uuid::new_v4().to_string(),
+        url: normalize_url(url),
+        original_url: None,
+        source_app: source_app.unwrap_or_default(),
+        source_context,
+        contact_name: None,
+        preview: None,
+        recommended_browser: None,
+        arrived_at: Some(current_timestamp()),
+        warning: None,
+    };
+
+    let mut notes = Vec::new();
+    if let Some(unwrapped) = unwrap_redirects(&link.url) {
+        notes.push(format!(
+            "Unwrapped redirect link; rules see {unwrapped} instead."
+        ));
+        link.original_url = Some(std::mem::replace(&mut link.url, unwrapped));
+    }
+    // Explaining a link must not contact the shortener, so only cached expansions are used.
+    match cached_expansion(app_handle, &link.url).await {
+        Some(Some(expanded)) => {
+            notes.push(format!(
+                "Expanded short link from the cache; rules see {expanded} instead."
+            ));
+            let original = std::mem::replace(&mut link.url, expanded);
+            link.original_url.get_or_insert(original);
+        }
+        Some(None) => notes.push(
+            "Short link is not expanded yet; routing expands it first, which may change the result."
+                .to_string(),
+        ),
+        None => {}
+    }
+    link.warning = Url::parse(&link.url).ok().and_then(|url| check_host(&url));
+    if let Some(warning) = &link.warning {
+        notes.push(warning.message.clone());
+    }
+
+    let facts = LinkFacts::from_url(&link.url).map(|facts| link_facts(facts, &link));
+    let evaluated_at = facts
+        .as_ref()
+        .map(|facts| facts.now)
+        .unwrap_or_else(|| Local::now().naive_local())
+        .format("%Y-%m-%d %H:%M (%a)")
+        .to_string();
+
+    if let Some(state) = app_handle.try_state::<PreferencesState>() {
+        if let Some(workspace) = state.active_workspace().await {
+            notes.push(format!(
+                "Only shared rules and rules in the '{workspace}' workspace apply."
+            ));
+        }
+        let cleanup = state.tracking_cleanup().await;
+        if let Some((_, removed)) = strip_tracking_parameters(&link.url, &cleanup) {
+            notes.push(format!(
+                "Tracking parameters would be stripped before launch: {}.",
+                removed.join(", ")
+            ));
+        }
+    }
+
+    let (rules, rule) = match &facts {
+        Some(facts) => {
+            let rules = match app_handle.try_state::<RulesState>() {
+                Some(state) => explain_rules(&state.active_rules().await, facts),
+                None => {
+                    notes.push("Routing rules are not loaded.".to_string());
+                    Vec::new()
+                }
+            };
+            (rules, find_rule(app_handle, facts).await)
+        }
+        None => {
+            notes.push(format!(
+                "'{}' is not a valid URL; no rules were evaluated.",
+                link.url
+            ));
+            (Vec::new(), None)
+        }
+    };
+
+    let plan = plan_route(app_handle, &link, rule.as_ref()).await;
+    notes.extend(plan.note);
+    if plan.via == RouteVia::Picker {
+        notes.push("No browser was chosen automatically; the picker would be shown.".to_string());
+    }
+
+    RouteExplanation {
+        input_url: url.to_string(),
+        normalized_url: link.url.clone(),
+        source_app: link.source_app.clone(),
+        source_context: link.source_context.clone(),
+        evaluated_at,
+        rules,
+        matched_rule_id: rule.map(|rule| rule.id),
+        via: plan.via,
+        decision: plan.decision,
+        notes,
+    }
+}
+
+/// The decision for a link whose scheme has a default browser configured.
+async fn scheme_decision(
+    app_handle: &tauri::AppHandle,
+    link: &IncomingLink,
+    scheme: &str,
+    browsers: &[BrowserDescriptor],
+) -> Option<LaunchDecision> {
+    let prefs = app_handle.try_state::<PreferencesState>()?;
+    let target = prefs
+        .scheme_handlers()
+        .await
+        .into_iter()
+        .find(|handler| handler.scheme == scheme)
+        .and_then(|handler| handler.target)?;
+    Some(preference_decision(link, &target, browsers))
+}
+
+async fn fallback_decision(
+    app_handle: &tauri::AppHandle,
+    link: &IncomingLink,
+    browsers: &[BrowserDescriptor],
+) -> Option<LaunchDecision> {
+    let prefs = app_handle.try_state::<PreferencesState>()?;
+    let fallback = prefs.fallback().await?;
+    Some(preference_decision(link, &fallback, browsers))
+}
+
+fn preference_decision(
+    link: &IncomingLink,
+    fallback: &FallbackPreference,
+    browsers: &[BrowserDescriptor],
+) -> LaunchDecision {
+    let profile_label = fallback
+        .profile
+        .as_ref()
+        .and_then(|p| p.label.clone())
+        .filter(|s| !s.is_empty());
+    let profile_directory = fallback
+        .profile
+        .as_ref()
+        .and_then(|p| p.directory.clone())
+        .filter(|s| !s.is_empty());
+    let browser = fallback
+        .browser_id
+        .as_deref()
+        .and_then(|id| browser_name_for_id(browsers, id))
+        .unwrap_or_else(|| fallback.browser.clone());
+
+    LaunchDecision {
+        id: link.id.clone(),
+        url: link.url.clone(),
+        original_url: link.original_url.clone(),
+        rule_id: None,
+        matched_url: None,
+        browser,
+        profile_label,
+        profile_directory,
+        persist: PersistChoice::Always,
+        decided_at: None,
+        source_app: link.source_app.clone(),
+        contact_name: link.contact_name.clone(),
+    }
+}
+
+/// Schemes whose payload may be all digits, so `tel:123` is not mistaken for `host:port`.
+const NUMERIC_PAYLOAD_SCHEMES: [&str; 6] = ["tel", "sms", "fax", "callto", "facetime", "geo"];
+/// Name suffixes reserved for local networks and development.
+const LOCAL_SUFFIXES: [&str; 6] = [
+    ".localhost",
+    ".local",
+    ".lan",
+    ".internal",
+    ".home.arpa",
+    ".test",
+];
+
 /// Normalize incoming URLs by ensuring they include a scheme and trimming whitespace.
+///
+/// Scheme-less input gets `http://` when it names a local or intranet host (localhost,
+/// loopback and private IPs, single-label names, `.local`-style suffixes) and `https://`
+/// otherwise. `host:port` is never read as a URL with scheme `host`.
 pub fn normalize_url(input: &str) -> String {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return String::new();
     }
 
-    if Url::parse(trimmed).is_ok() {
+    if Url::parse(trimmed).is_ok() && !is_host_with_port(trimmed) {
         return trimmed.to_string();
     }
 
-    let candidate = format!(
-        "https://{}",
-        trimmed
-            .trim_start_matches("https://")
-            .trim_start_matches("http://")
-    );
+    let rest = trimmed
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("//");
+    let rest = bracket_bare_ipv6(rest);
+    let scheme = if is_local_host(authority_host(&rest)) {
+        "http"
+    } else {
+        "https"
+    };
+    let candidate = format!("{scheme}://{rest}");
 
     if Url::parse(&candidate).is_ok() {
         candidate
@@ -370,22 +872,138 @@ pub fn normalize_url(input: &str) -> String {
     }
 }
 
+/// Whether `input` parsed as `scheme:payload` is really `host:port`, e.g. `localhost:3000`.
+fn is_host_with_port(input: &str) -> bool {
+    let Some((scheme, payload)) = input.split_once(':') else {
+        return false;
+    };
+    if payload.starts_with("//")
+        || NUMERIC_PAYLOAD_SCHEMES
+            .iter()
+            .any(|known| scheme.eq_ignore_ascii_case(known))
+    {
+        return false;
+    }
+    let port = payload.split(['/', '?', '#']).next().unwrap_or_default();
+    !port.is_empty() && port.len() <= 5 && port.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Wraps a bare IPv6 literal such as `::1` in brackets so it can carry a scheme.
+fn bracket_bare_ipv6(rest: &str) -> String {
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, tail) = rest.split_at(end);
+    if authority.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("[{authority}]{tail}")
+    } else {
+        rest.to_string()
+    }
+}
+
+/// The host part of `host[:port][/path]`, without brackets around IPv6 literals.
+fn authority_host(rest: &str) -> &str {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    if let Some(literal) = authority.strip_prefix('[') {
+        return literal.split(']').next().unwrap_or_default();
+    }
+    authority.split(':').next().unwrap_or_default()
+}
+
+/// Hosts that are reached over plain http: dev servers, private networks and intranet names.
+fn is_local_host(host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if let Ok(ip) = host.parse::<std::net::IpAddr>() {
+        return match ip {
+            std::net::IpAddr::V4(ip) => {
+                ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+            }
+            std::net::IpAddr::V6(ip) => {
+                let first = ip.segments()[0];
+                ip.is_loopback()
+                    || ip.is_unspecified()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80
+            }
+        };
+    }
+
+    !host.is_empty()
+        && (host == "localhost"
+            || !host.contains('.')
+            || LOCAL_SUFFIXES.iter().any(|suffix| host.ends_with(suffix)))
+}
+
 impl Default for RoutingService {
     fn default() -> Self {
         Self::new()
     }
 }
 
-fn resolve_browser_path(name: &str) -> Option<PathBuf> {
+/// The executable and fixed arguments used to start a browser; the link goes in between.
+#[derive(Debug, Clone)]
+struct BrowserCommand {
+    program: PathBuf,
+    leading_args: Vec<String>,
+    trailing_args: Vec<String>,
+    /// Browser whose profile arguments apply, when it differs from the name it is listed under.
+    profile_browser: Option<String>,
+}
+
+impl BrowserCommand {
+    fn from_path(program: PathBuf) -> Self {
+        Self {
+            program,
+            leading_args: Vec::new(),
+            trailing_args: Vec::new(),
+            profile_browser: None,
+        }
+    }
+}
+
+fn resolve_browser_command(catalog: &BrowserCatalog, name: &str) -> Option<BrowserCommand> {
+    // Flatpak and Snap browsers are started through their sandbox launcher.
+    #[cfg(target_os = "linux")]
+    if let Some(browser) = crate::sandbox::find_listed(&catalog.descriptors, name) {
+        let (program, leading_args) = browser.launch_command();
+        return Some(BrowserCommand {
+            program,
+            leading_args,
+            trailing_args: Vec::new(),
+            profile_browser: Some(browser.browser.to_string()),
+        });
+    }
+
     let needle = normalize_browser_key(name);
-    for browser in get_all_existing_browsers() {
-        if normalize_browser_key(browser.name) == needle {
-            if let Some(path) = get_browser_path(&browser) {
-                return Some(path);
+    let path = catalog
+        .descriptors
+        .iter()
+        .filter(|browser| normalize_browser_key(&browser.display_name) == needle)
+        .find_map(|browser| browser.executable.clone());
+
+    // A desktop entry carries the command line the browser expects to be started with.
+    #[cfg(target_os = "linux")]
+    {
+        let template = crate::browser_details::find_desktop_browser(
+            &catalog.desktop_browsers,
+            name,
+            path.as_deref(),
+        )
+        .and_then(|browser| browser.exec_template());
+        if let Some(template) = template {
+            if let Some(program) = crate::desktop_entries::resolve_program(&template.program) {
+                return Some(BrowserCommand {
+                    program,
+                    leading_args: template.leading_args,
+                    trailing_args: template.trailing_args,
+                    profile_browser: None,
+                });
             }
         }
     }
-    None
+
+    path.map(BrowserCommand::from_path)
 }
 
 /// Normalize a browser name into a lowercase alphanumeric key.
@@ -398,15 +1016,17 @@ pub fn normalize_browser_key(value: &str) -> String {
 }
 
 fn launch_with_browser(
-    path: PathBuf,
+    browser: BrowserCommand,
     browser_name: &str,
     url: &str,
     profile_directory: Option<String>,
 ) -> Result<(), String> {
-    let mut command = Command::new(&path);
+    let mut command = Command::new(&browser.program);
+    command.args(&browser.leading_args);
 
     if let Some(profile_dir) = profile_directory.as_deref() {
-        add_profile_args(&mut command, browser_name, profile_dir);
+        let profile_browser = browser.profile_browser.as_deref().unwrap_or(browser_name);
+        add_profile_args(&mut command, profile_browser, profile_dir);
     }
 
     if let Some(user_data_dir) = browser_user_data_dir(browser_name) {
@@ -414,6 +1034,7 @@ fn launch_with_browser(
     }
 
     command.arg(url);
+    command.args(&browser.trailing_args);
 
     #[cfg(target_os = "windows")]
     {
@@ -433,24 +1054,11 @@ pub fn add_profile_args(command: &mut Command, browser_name: &str, profile: &str
 
     let key = normalize_browser_key(browser_name);
     match key.as_str() {
-        k if matches!(
-            k,
-            "chrome"
-                | "chromebeta"
-                | "chromedev"
-                | "chromecanary"
-                | "chromium"
-                | "edge"
-                | "edgebeta"
-                | "edgedev"
-                | "edgecanary"
-                | "brave"
-                | "vivaldi"
-                | "thorium"
-        ) => {
+        "chrome" | "chromebeta" | "chromedev" | "chromecanary" | "chromium" | "edge"
+        | "edgebeta" | "edgedev" | "edgecanary" | "brave" | "vivaldi" | "thorium" => {
             command.arg(format!("--profile-directory={trimmed}"));
         }
-        k if matches!(k, "firefox" | "firefoxbeta" | "waterfox") => {
+        "firefox" | "firefoxbeta" | "waterfox" => {
             command.args(["-P", trimmed]);
         }
         _ => {}
@@ -598,10 +1206,10 @@ fn append_log(app: &tauri::AppHandle, message: &str) {
     if let Some(store) = app.try_state::<crate::diagnostics::DiagnosticsState>() {
         let entry = store.record(message.to_string());
         let _ = app.emit("diagnostics://entry", entry.clone());
-        print!("[{}] {}\n", entry.timestamp, entry.message);
+        println!("[{}] {}", entry.timestamp, entry.message);
     } else {
         let timestamp = Utc::now().to_rfc3339();
-        print!("[{timestamp}] {message}\n");
+        println!("[{timestamp}] {message}");
     }
 }
 
@@ -632,7 +1240,7 @@ fn current_timestamp() -> String {
     Utc::now().to_rfc3339()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SimulatedLinkPayload {
     #[serde(default)]
     pub url: Option<String>,
@@ -661,6 +1269,7 @@ pub async fn simulate_link_payload(payload: Option<SimulatedLinkPayload>) -> Inc
     IncomingLink {
         id: Uuid::new_v4().to_string(),
         url: url.clone(),
+        original_url: None,
         source_app,
         source_context: data
             .source_context
@@ -671,18 +1280,7 @@ pub async fn simulate_link_payload(payload: Option<SimulatedLinkPayload>) -> Inc
             .or_else(|| Some("Shared link detected.".to_string())),
         recommended_browser: None,
         arrived_at: Some(current_timestamp()),
-    }
-}
-
-impl Default for SimulatedLinkPayload {
-    fn default() -> Self {
-        Self {
-            url: None,
-            source_app: None,
-            contact_name: None,
-            source_context: None,
-            preview: None,
-        }
+        warning: None,
     }
 }
 
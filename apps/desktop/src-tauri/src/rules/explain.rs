@@ -0,0 +1,123 @@
+use super::matching::{CompiledCondition, LinkFacts};
+use super::validation::validate_condition;
+use crate::domain::models::{Condition, Rule};
+use serde::Serialize;
+
+/// Why a single rule did or did not route a link.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleEvaluation {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub priority: i32,
+    pub status: RuleStatus,
+    pub reason: String,
+    pub conditions: Vec<ConditionEvaluation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleStatus {
+    /// First matching rule; this one decides the route.
+    Selected,
+    /// Matches, but a higher-priority rule was selected first.
+    Shadowed,
+    NotMatched,
+    Disabled,
+    /// Has a condition that can never match, so it is skipped.
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionEvaluation {
+    pub fact: String,
+    pub operator: String,
+    pub value: String,
+    /// The fact value the condition was compared against.
+    pub subject: Option<String>,
+    pub matched: bool,
+    pub error: Option<String>,
+}
+
+/// Evaluates every rule against the link in priority order without short-circuiting.
+pub fn explain_rules(rules: &[Rule], facts: &LinkFacts) -> Vec<RuleEvaluation> {
+    let mut ordered = rules.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|rule| rule.priority);
+
+    let mut selected: Option<&Rule> = None;
+    ordered
+        .into_iter()
+        .map(|rule| {
+            let conditions = rule
+                .conditions
+                .iter()
+                .map(|condition| explain_condition(condition, facts))
+                .collect::<Vec<_>>();
+
+            let (status, reason) = if !rule.enabled {
+                (RuleStatus::Disabled, "Rule is disabled.".to_string())
+            } else if conditions.is_empty() {
+                (RuleStatus::Invalid, "Rule has no conditions.".to_string())
+            } else if let Some(error) = conditions.iter().find_map(|c| c.error.clone()) {
+                (RuleStatus::Invalid, error)
+            } else if let Some(failed) = conditions.iter().find(|c| !c.matched) {
+                (RuleStatus::NotMatched, describe_miss(failed))
+            } else if let Some(winner) = selected {
+                (
+                    RuleStatus::Shadowed,
+                    format!(
+                        "All conditions matched, but '{}' has higher priority.",
+                        winner.rule_name
+                    ),
+                )
+            } else {
+                selected = Some(rule);
+                (RuleStatus::Selected, "All conditions matched.".to_string())
+            };
+
+            RuleEvaluation {
+                rule_id: rule.id.clone(),
+                rule_name: rule.rule_name.clone(),
+                priority: rule.priority,
+                status,
+                reason,
+                conditions,
+            }
+        })
+        .collect()
+}
+
+fn explain_condition(condition: &Condition, facts: &LinkFacts) -> ConditionEvaluation {
+    let (subject, matched, error) = match CompiledCondition::compile(condition) {
+        Some(compiled) => (Some(compiled.subject(facts)), compiled.matches(facts), None),
+        None => {
+            let error = validate_condition(condition)
+                .err()
+                .unwrap_or_else(|| "Condition can never match.".to_string());
+            (None, false, Some(error))
+        }
+    };
+
+    ConditionEvaluation {
+        fact: condition.fact.clone(),
+        operator: condition.operator.clone(),
+        value: condition.value.clone(),
+        subject,
+        matched,
+        error,
+    }
+}
+
+fn describe_miss(condition: &ConditionEvaluation) -> String {
+    match condition.subject.as_deref() {
+        Some("") | None => format!(
+            "{} {} '{}' had nothing to compare against.",
+            condition.fact, condition.operator, condition.value
+        ),
+        Some(subject) => format!(
+            "{} {} '{}' did not match '{}'.",
+            condition.fact, condition.operator, condition.value, subject
+        ),
+    }
+}
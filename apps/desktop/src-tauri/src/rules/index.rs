@@ -0,0 +1,171 @@
+use super::matching::{ascii_host_pattern, CompiledRule, LinkFacts};
+use super::rewrite::placeholder_glob;
+use crate::domain::models::Rule;
+use regex::{RegexSet, RegexSetBuilder};
+use std::collections::HashMap;
+
+/// Lazy DFA cache budget for the combined regex set. The default is sized for a single
+/// pattern and thrashes once a few hundred rules share the `https://` prefix.
+const REGEX_SET_DFA_LIMIT: usize = 32 << 20;
+
+/// Precompiled lookup structure over a rule set, rebuilt whenever the rules change.
+///
+/// Host and wildcard rules are keyed by their literal host suffix in a label trie and
+/// regex rules share a single `RegexSet`, so a lookup only verifies the handful of rules
+/// that can plausibly match instead of scanning the whole list.
+#[derive(Debug, Default)]
+pub struct RuleIndex {
+    rules: Vec<CompiledRule>,
+    hosts: TrieNode,
+    regexes: Option<RegexSet>,
+    regex_rules: Vec<usize>,
+    unkeyed: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<String, TrieNode>,
+    /// Rules whose host must end exactly at this node.
+    exact: Vec<usize>,
+    /// Rules that may match any host at or below this node.
+    subtree: Vec<usize>,
+}
+
+enum IndexKey {
+    Exact(Vec<String>),
+    Subtree(Vec<String>),
+    Regex(String),
+    Unkeyed,
+}
+
+impl RuleIndex {
+    pub fn build(rules: &[Rule]) -> Self {
+        let mut enabled = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .cloned()
+            .collect::<Vec<_>>();
+        enabled.sort_by_key(|rule| rule.priority);
+
+        let mut index = RuleIndex::default();
+        let mut patterns = Vec::new();
+
+        for (position, rule) in enabled.into_iter().enumerate() {
+            let compiled = CompiledRule::new(rule);
+            if compiled.is_viable() {
+                match index_key(&compiled.rule) {
+                    IndexKey::Exact(labels) => index.hosts.insert(&labels).exact.push(position),
+                    IndexKey::Subtree(labels) => index.hosts.insert(&labels).subtree.push(position),
+                    IndexKey::Regex(pattern) => {
+                        patterns.push(pattern);
+                        index.regex_rules.push(position);
+                    }
+                    IndexKey::Unkeyed => index.unkeyed.push(position),
+                }
+            }
+            index.rules.push(compiled);
+        }
+
+        if !patterns.is_empty() {
+            match RegexSetBuilder::new(&patterns)
+                .case_insensitive(true)
+                .dfa_size_limit(REGEX_SET_DFA_LIMIT)
+                .build()
+            {
+                Ok(set) => index.regexes = Some(set),
+                // Too large for a single set; verify those rules one by one instead.
+                Err(_) => index.unkeyed.append(&mut index.regex_rules),
+            }
+        }
+
+        index
+    }
+
+    /// Returns the highest-priority rule matching the link, if any.
+    pub fn find(&self, facts: &LinkFacts) -> Option<&Rule> {
+        let mut candidates = self.unkeyed.clone();
+        self.hosts.collect(&facts.host(), &mut candidates);
+        if let Some(set) = &self.regexes {
+            candidates.extend(
+                set.matches(facts.url.as_str())
+                    .iter()
+                    .map(|matched| self.regex_rules[matched]),
+            );
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+            .into_iter()
+            .map(|position| &self.rules[position])
+            .find(|compiled| compiled.matches(facts))
+            .map(|compiled| &compiled.rule)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl TrieNode {
+    fn insert(&mut self, labels: &[String]) -> &mut TrieNode {
+        labels.iter().fold(self, |node, label| {
+            node.children.entry(label.clone()).or_default()
+        })
+    }
+
+    fn collect(&self, host: &str, candidates: &mut Vec<usize>) {
+        let mut node = self;
+        for label in host.rsplit('.') {
+            match node.children.get(label) {
+                Some(child) => {
+                    node = child;
+                    candidates.extend_from_slice(&node.subtree);
+                }
+                None => return,
+            }
+        }
+        candidates.extend_from_slice(&node.exact);
+    }
+}
+
+/// Picks the trie or regex key for a rule from its first URL condition.
+fn index_key(rule: &Rule) -> IndexKey {
+    let Some(condition) = rule.conditions.iter().find(|c| c.fact == "url") else {
+        return IndexKey::Unkeyed;
+    };
+    let pattern = condition.value.trim();
+
+    match condition.operator.as_str() {
+        "host" => IndexKey::Exact(reversed_labels(&ascii_host_pattern(pattern))),
+        "wildcard" => {
+            let pattern = placeholder_glob(pattern);
+            let host = pattern.split('/').next().unwrap_or_default();
+            match host.rfind('*') {
+                None => IndexKey::Exact(reversed_labels(&ascii_host_pattern(host))),
+                Some(star) => {
+                    // Only labels fully inside the literal tail are guaranteed to appear.
+                    let mut labels = host[star + 1..].split('.').skip(1).peekable();
+                    if labels.peek().is_none() {
+                        return IndexKey::Unkeyed;
+                    }
+                    let suffix = labels.collect::<Vec<_>>().join(".");
+                    if suffix.split('.').any(str::is_empty) {
+                        return IndexKey::Unkeyed;
+                    }
+                    IndexKey::Subtree(reversed_labels(&ascii_host_pattern(&suffix)))
+                }
+            }
+        }
+        "regex" => IndexKey::Regex(pattern.to_string()),
+        _ => IndexKey::Unkeyed,
+    }
+}
+
+fn reversed_labels(host: &str) -> Vec<String> {
+    host.rsplit('.').map(str::to_string).collect()
+}
@@ -0,0 +1,286 @@
+use super::matching::{wildcard_matches, CompiledCondition, CompiledRule};
+use super::snapshot::{applies_in, RuleKind, RulesSnapshot};
+use super::validation::validate_condition;
+use crate::domain::models::{Condition, Rule};
+use serde::Serialize;
+
+/// A problem found in the rule set, attached to the rule it concerns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleLint {
+    pub rule_id: String,
+    pub kind: RuleKind,
+    pub code: LintCode,
+    pub message: String,
+    /// The earlier rule responsible, for shadowing, duplicates and conflicts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_rule_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LintCode {
+    /// An earlier rule with a broader pattern catches every link this one would.
+    Shadowed,
+    /// Same pattern, conditions and target as an earlier rule.
+    Duplicate,
+    /// Same pattern and conditions as an earlier rule but a different target.
+    Conflict,
+    /// A condition can never match, so the rule is skipped.
+    Invalid,
+    MissingBrowser,
+    MissingProfile,
+}
+
+/// Catalog ids of the browsers and profiles installed on this machine.
+#[derive(Debug, Clone, Default)]
+pub struct BrowserInventory {
+    /// Catalog id prefixes (`<browser>`) of installed browsers.
+    pub browsers: Vec<String>,
+    /// Full catalog ids (`<browser>__<profile>`), including each browser's `default`.
+    pub ids: Vec<String>,
+}
+
+impl BrowserInventory {
+    fn check(&self, browser_id: &str) -> Option<(LintCode, String)> {
+        if self.ids.iter().any(|id| id == browser_id) {
+            return None;
+        }
+        let (base, profile) = browser_id
+            .split_once("__")
+            .unwrap_or((browser_id, "default"));
+        if self.browsers.iter().any(|installed| installed == base) {
+            Some((
+                LintCode::MissingProfile,
+                format!("Profile '{profile}' was not found for browser '{base}'."),
+            ))
+        } else {
+            Some((
+                LintCode::MissingBrowser,
+                format!("Browser '{base}' is not installed."),
+            ))
+        }
+    }
+}
+
+/// Reports dead, redundant and broken rules without changing anything.
+pub fn lint_rules(snapshot: &RulesSnapshot, inventory: &BrowserInventory) -> Vec<RuleLint> {
+    let mut lints = Vec::new();
+    let rules = snapshot.to_domain_rules();
+
+    for (position, rule) in rules.iter().enumerate() {
+        let compiled = CompiledRule::new(rule.clone());
+        if !compiled.is_viable() {
+            let reason = rule
+                .conditions
+                .iter()
+                .find_map(|condition| validate_condition(condition).err())
+                .unwrap_or_else(|| "Rule has a condition that can never match.".to_string());
+            lints.push(lint(
+                rule,
+                RuleKind::Domain,
+                LintCode::Invalid,
+                reason,
+                None,
+            ));
+            continue;
+        }
+
+        if !rule.enabled {
+            continue;
+        }
+
+        // An earlier rule only hides this one if it applies in every workspace this one does.
+        let workspace = snapshot.domain_rules[position].workspace.as_deref();
+        let earlier = rules[..position]
+            .iter()
+            .zip(&snapshot.domain_rules)
+            .filter(|(other, stored)| {
+                other.enabled
+                    && applies_in(stored.workspace.as_deref(), workspace)
+                    && CompiledRule::new((*other).clone()).is_viable()
+            })
+            .map(|(other, _)| other);
+        for other in earlier {
+            if let Some((code, message)) = compare_domain_rules(other, rule) {
+                lints.push(lint(rule, RuleKind::Domain, code, message, Some(&other.id)));
+                break;
+            }
+        }
+    }
+
+    for (position, rule) in snapshot.file_type_rules.iter().enumerate() {
+        if let Some(other) = snapshot.file_type_rules[..position]
+            .iter()
+            .find(|other| {
+                other.extension == rule.extension
+                    && applies_in(other.workspace.as_deref(), rule.workspace.as_deref())
+            })
+        {
+            let (code, message) =
+                if other.browser_id == rule.browser_id && other.policy == rule.policy {
+                    (
+                        LintCode::Duplicate,
+                        format!("Duplicates the earlier rule for '{}'.", other.extension),
+                    )
+                } else {
+                    (
+                        LintCode::Conflict,
+                        format!(
+                            "Never applies: the earlier rule for '{}' opens these files in {}.",
+                            other.extension,
+                            target_label(&other.browser_label)
+                        ),
+                    )
+                };
+            lints.push(RuleLint {
+                rule_id: rule.id.clone(),
+                kind: RuleKind::FileType,
+                code,
+                message,
+                related_rule_id: Some(other.id.clone()),
+            });
+        }
+    }
+
+    let targets = snapshot
+        .domain_rules
+        .iter()
+        .map(|rule| (&rule.id, RuleKind::Domain, rule.browser_id.as_deref()))
+        .chain(
+            snapshot
+                .file_type_rules
+                .iter()
+                .map(|rule| (&rule.id, RuleKind::FileType, rule.browser_id.as_deref())),
+        );
+    for (id, kind, browser_id) in targets {
+        if let Some((code, message)) = browser_id.and_then(|browser_id| inventory.check(browser_id))
+        {
+            lints.push(RuleLint {
+                rule_id: id.clone(),
+                kind,
+                code,
+                message,
+                related_rule_id: None,
+            });
+        }
+    }
+
+    lints
+}
+
+fn lint(
+    rule: &Rule,
+    kind: RuleKind,
+    code: LintCode,
+    message: String,
+    related: Option<&String>,
+) -> RuleLint {
+    RuleLint {
+        rule_id: rule.id.clone(),
+        kind,
+        code,
+        message,
+        related_rule_id: related.cloned(),
+    }
+}
+
+fn target_label(label: &str) -> String {
+    if label.trim().is_empty() {
+        "another browser".to_string()
+    } else {
+        format!("'{label}'")
+    }
+}
+
+/// Decides whether `earlier` catches every link `later` would, and how to describe it.
+fn compare_domain_rules(earlier: &Rule, later: &Rule) -> Option<(LintCode, String)> {
+    let (earlier_url, earlier_extra) = split_conditions(earlier)?;
+    let (later_url, later_extra) = split_conditions(later)?;
+
+    // Extra conditions narrow a rule; the earlier one only covers if it is no narrower.
+    if !earlier_extra.iter().all(|condition| {
+        later_extra
+            .iter()
+            .any(|other| same_condition(condition, other))
+    }) {
+        return None;
+    }
+
+    let same_url = same_condition(earlier_url, later_url);
+    if same_url && earlier_extra.len() == later_extra.len() {
+        let same_target = earlier.action.browser == later.action.browser
+            && earlier.action.policy == later.action.policy
+            && earlier.action.url == later.action.url;
+        return Some(if same_target {
+            (
+                LintCode::Duplicate,
+                format!("Duplicates the earlier rule '{}'.", earlier.rule_name),
+            )
+        } else {
+            (
+                LintCode::Conflict,
+                format!(
+                    "Never fires: the earlier rule '{}' has the same pattern but another target.",
+                    earlier.rule_name
+                ),
+            )
+        });
+    }
+
+    (same_url || covers(earlier_url, later_url)).then(|| {
+        (
+            LintCode::Shadowed,
+            format!(
+                "Never fires: the earlier rule '{}' already matches every link this one does.",
+                earlier.rule_name
+            ),
+        )
+    })
+}
+
+fn split_conditions(rule: &Rule) -> Option<(&Condition, Vec<&Condition>)> {
+    let url = rule.conditions.iter().find(|c| c.fact == "url")?;
+    let extra = rule
+        .conditions
+        .iter()
+        .filter(|c| !std::ptr::eq(*c, url))
+        .collect();
+    Some((url, extra))
+}
+
+fn same_condition(a: &Condition, b: &Condition) -> bool {
+    if a.fact != b.fact || a.operator != b.operator {
+        return false;
+    }
+    match (glob_of(a), glob_of(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.value.trim().eq_ignore_ascii_case(b.value.trim()),
+    }
+}
+
+/// Whether every URL matching the `later` host/wildcard condition also matches `earlier`.
+///
+/// Stars in the later pattern are replaced by a character no host or path contains, so the
+/// earlier glob can only match it by absorbing each star into one of its own.
+fn covers(earlier: &Condition, later: &Condition) -> bool {
+    let (Some(earlier), Some(later)) = (glob_of(earlier), glob_of(later)) else {
+        return false;
+    };
+
+    let subject = match (earlier.contains('/'), later.split_once('/')) {
+        (false, Some((host, _))) => host.to_string(),
+        (true, None) => return false,
+        _ => later.clone(),
+    };
+    wildcard_matches(&earlier, &subject.replace('*', "\u{1}"))
+}
+
+/// The canonical glob of a host or wildcard url condition; a host is a glob without stars.
+fn glob_of(condition: &Condition) -> Option<String> {
+    match CompiledCondition::compile(condition)? {
+        CompiledCondition::Host(host) => Some(host),
+        CompiledCondition::Wildcard { pattern, .. } => Some(pattern),
+        _ => None,
+    }
+}
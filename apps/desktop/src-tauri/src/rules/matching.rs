@@ -0,0 +1,391 @@
+use super::rewrite::placeholder_glob;
+use super::schedule::Schedule;
+use super::url_parts::UrlPart;
+use super::validation::normalize_extension;
+use crate::domain::models::{Condition, Rule};
+use chrono::{Local, NaiveDateTime};
+use regex::{Regex, RegexBuilder};
+use url::{Host, Url};
+
+/// Facts about an incoming link that rule conditions are evaluated against.
+#[derive(Debug, Clone)]
+pub struct LinkFacts {
+    pub url: Url,
+    pub source_app: String,
+    pub source_context: Option<String>,
+    /// Local wall-clock time the link is routed at, for schedule conditions.
+    pub now: NaiveDateTime,
+}
+
+impl LinkFacts {
+    /// Builds facts for a normalized URL, returning `None` when it does not parse.
+    pub fn from_url(url: &str) -> Option<Self> {
+        Url::parse(url).ok().map(|url| Self {
+            url,
+            source_app: String::new(),
+            source_context: None,
+            now: Local::now().naive_local(),
+        })
+    }
+
+    /// Attaches the application and context the link was handed over from.
+    pub fn with_source(mut self, source_app: &str, source_context: Option<&str>) -> Self {
+        self.source_app = source_app.to_string();
+        self.source_context = source_context.map(str::to_string);
+        self
+    }
+
+    /// Evaluates schedule conditions as if the link arrived at `now`.
+    pub fn with_time(mut self, now: NaiveDateTime) -> Self {
+        self.now = now;
+        self
+    }
+
+    pub fn host(&self) -> String {
+        self.url
+            .host_str()
+            .unwrap_or_default()
+            .trim_end_matches('.')
+            .to_ascii_lowercase()
+    }
+
+    fn host_and_path(&self) -> String {
+        format!("{}{}", self.host(), self.url.path().to_lowercase())
+    }
+
+    /// The extension of the file the link points at, as `.ext`.
+    ///
+    /// A file name in a download query parameter wins over the path, since the path of a
+    /// dynamic download usually names the script (`download.php?filename=report.pdf`).
+    pub fn extension(&self) -> Option<String> {
+        file_extension(&self.file_name()?)
+    }
+
+    /// The lowercase name of the file the link points at, chosen as for [`Self::extension`].
+    pub fn file_name(&self) -> Option<String> {
+        self.url
+            .query_pairs()
+            .filter(|(key, _)| FILENAME_PARAMS.contains(&key.to_ascii_lowercase().as_str()))
+            .map(|(_, value)| disposition_filename(&value).to_string())
+            .find(|name| file_extension(name).is_some())
+            .or_else(|| Some(self.url.path_segments()?.next_back()?.to_string()))
+            .map(|name| {
+                let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+                name.to_ascii_lowercase()
+            })
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Whether the file name ends in `extension`, which may span dots such as `.tar.gz`.
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.file_name().is_some_and(|name| {
+            let extension = extension.to_ascii_lowercase();
+            name.len() > extension.len() && name.ends_with(&extension)
+        })
+    }
+}
+
+/// Query parameters that commonly carry the name of a downloaded file.
+const FILENAME_PARAMS: [&str; 5] = [
+    "filename",
+    "file",
+    "download",
+    "response-content-disposition",
+    "rscd",
+];
+
+/// Extracts the file name from a `Content-Disposition` value, or returns the value as is.
+fn disposition_filename(value: &str) -> &str {
+    let lowered = value.to_ascii_lowercase();
+    let Some(start) = ["filename*=", "filename="]
+        .iter()
+        .find_map(|marker| lowered.rfind(marker).map(|index| index + marker.len()))
+    else {
+        return value;
+    };
+
+    let name = value[start..].split(';').next().unwrap_or_default().trim();
+    let name = name.rsplit_once("''").map_or(name, |(_, name)| name);
+    name.trim_matches('"')
+}
+
+fn file_extension(name: &str) -> Option<String> {
+    let name = name.rsplit(['/', '\\']).next()?;
+    let (stem, extension) = name.rsplit_once('.')?;
+    let plausible = !stem.is_empty()
+        && (1..=10).contains(&extension.len())
+        && extension.chars().all(|c| c.is_ascii_alphanumeric());
+    if plausible {
+        Some(format!(".{}", extension.to_ascii_lowercase()))
+    } else {
+        None
+    }
+}
+
+/// A condition with its pattern parsed once so it can be evaluated repeatedly.
+#[derive(Debug, Clone)]
+pub enum CompiledCondition {
+    Host(String),
+    Wildcard {
+        pattern: String,
+        with_path: bool,
+    },
+    Regex(Regex),
+    Text {
+        fact: TextFact,
+        matcher: TextMatcher,
+    },
+    Schedule(Schedule),
+    /// Extension of the linked file, in `.ext` form.
+    Extension(String),
+    UrlPart(UrlPart),
+}
+
+/// Free-text facts describing where a link came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFact {
+    SourceApp,
+    SourceContext,
+}
+
+impl TextFact {
+    fn parse(fact: &str) -> Option<Self> {
+        match fact {
+            "source_app" => Some(TextFact::SourceApp),
+            "source_context" => Some(TextFact::SourceContext),
+            _ => None,
+        }
+    }
+
+    fn value(self, facts: &LinkFacts) -> Option<&str> {
+        match self {
+            TextFact::SourceApp => Some(facts.source_app.as_str()).filter(|s| !s.is_empty()),
+            TextFact::SourceContext => facts.source_context.as_deref(),
+        }
+    }
+}
+
+/// Case-insensitive comparison applied to a text fact.
+#[derive(Debug, Clone)]
+pub enum TextMatcher {
+    Equals(String),
+    Contains(String),
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    fn compile(operator: &str, value: &str) -> Option<Self> {
+        match operator {
+            "equals" => Some(TextMatcher::Equals(value.to_lowercase())),
+            "contains" => Some(TextMatcher::Contains(value.to_lowercase())),
+            "regex" => RegexBuilder::new(value)
+                .case_insensitive(true)
+                .build()
+                .ok()
+                .map(TextMatcher::Regex),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, subject: &str) -> bool {
+        match self {
+            TextMatcher::Equals(expected) => subject.trim().to_lowercase() == *expected,
+            TextMatcher::Contains(needle) => subject.to_lowercase().contains(needle.as_str()),
+            TextMatcher::Regex(regex) => regex.is_match(subject),
+        }
+    }
+}
+
+impl CompiledCondition {
+    /// Compiles a condition, returning `None` for facts, operators or patterns that can never match.
+    pub fn compile(condition: &Condition) -> Option<Self> {
+        let pattern = condition.value.trim();
+        if pattern.is_empty() {
+            return None;
+        }
+
+        match (condition.fact.as_str(), condition.operator.as_str()) {
+            ("url", "host") => Some(CompiledCondition::Host(ascii_host_pattern(pattern))),
+            ("url", "wildcard") => Some(CompiledCondition::Wildcard {
+                pattern: wildcard_pattern(&placeholder_glob(pattern)),
+                with_path: pattern.contains('/'),
+            }),
+            ("url", "regex") => RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .ok()
+                .map(CompiledCondition::Regex),
+            ("extension", "equals") => normalize_extension(pattern)
+                .ok()
+                .map(CompiledCondition::Extension),
+            ("path" | "query" | "port" | "scheme", operator) => {
+                UrlPart::parse(&condition.fact, operator, pattern)
+                    .ok()
+                    .map(CompiledCondition::UrlPart)
+            }
+            ("weekday" | "time" | "date", operator) => {
+                Schedule::parse(&condition.fact, operator, pattern)
+                    .ok()
+                    .map(CompiledCondition::Schedule)
+            }
+            (fact, operator) => {
+                let fact = TextFact::parse(fact)?;
+                let matcher = TextMatcher::compile(operator, pattern)?;
+                Some(CompiledCondition::Text { fact, matcher })
+            }
+        }
+    }
+
+    /// The fact value this condition is compared against, for explanations.
+    pub fn subject(&self, facts: &LinkFacts) -> String {
+        match self {
+            CompiledCondition::Host(_) => facts.host(),
+            CompiledCondition::Wildcard { with_path, .. } => {
+                if *with_path {
+                    facts.host_and_path()
+                } else {
+                    facts.host()
+                }
+            }
+            CompiledCondition::Regex(_) => facts.url.to_string(),
+            CompiledCondition::Text { fact, .. } => {
+                fact.value(facts).unwrap_or_default().to_string()
+            }
+            CompiledCondition::Schedule(schedule) => schedule.subject(facts.now),
+            CompiledCondition::Extension(_) => facts.file_name().unwrap_or_default(),
+            CompiledCondition::UrlPart(part) => part.subject(&facts.url),
+        }
+    }
+
+    pub fn matches(&self, facts: &LinkFacts) -> bool {
+        match self {
+            CompiledCondition::Host(host) => facts.host() == *host,
+            // Links without a host, such as `mailto:` or `file:`, never match host
+            // patterns, so a catch-all `*` rule only catches web-style links. Local files
+            // are targeted with file type rules, which match the end of the path instead.
+            CompiledCondition::Wildcard { .. } if facts.host().is_empty() => false,
+            CompiledCondition::Wildcard { pattern, with_path } => {
+                let subject = if *with_path {
+                    facts.host_and_path()
+                } else {
+                    facts.host()
+                };
+                wildcard_matches(pattern, &subject)
+            }
+            CompiledCondition::Regex(regex) => regex.is_match(facts.url.as_str()),
+            CompiledCondition::Text { fact, matcher } => fact
+                .value(facts)
+                .is_some_and(|subject| matcher.matches(subject)),
+            CompiledCondition::Schedule(schedule) => schedule.matches(facts.now),
+            CompiledCondition::Extension(extension) => facts.has_extension(extension),
+            CompiledCondition::UrlPart(part) => part.matches(&facts.url),
+        }
+    }
+}
+
+/// A rule whose conditions have been compiled up front.
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pub rule: Rule,
+    conditions: Option<Vec<CompiledCondition>>,
+}
+
+impl CompiledRule {
+    pub fn new(rule: Rule) -> Self {
+        let conditions = rule
+            .conditions
+            .iter()
+            .map(CompiledCondition::compile)
+            .collect::<Option<Vec<_>>>()
+            .filter(|conditions| !conditions.is_empty());
+        Self { rule, conditions }
+    }
+
+    /// Whether every condition compiled, i.e. the rule can match anything at all.
+    pub fn is_viable(&self) -> bool {
+        self.conditions.is_some()
+    }
+
+    /// A rule matches when it has at least one condition and every condition holds.
+    pub fn matches(&self, facts: &LinkFacts) -> bool {
+        self.conditions
+            .as_ref()
+            .is_some_and(|conditions| conditions.iter().all(|c| c.matches(facts)))
+    }
+}
+
+/// Returns the first enabled rule whose conditions all hold, ordered by ascending priority.
+///
+/// Compiles every rule on each call; the routing pipeline uses [`super::index::RuleIndex`].
+pub fn find_matching_rule<'a>(rules: &'a [Rule], facts: &LinkFacts) -> Option<&'a Rule> {
+    let mut candidates = rules.iter().filter(|rule| rule.enabled).collect::<Vec<_>>();
+    candidates.sort_by_key(|rule| rule.priority);
+    candidates
+        .into_iter()
+        .find(|rule| rule_matches(rule, facts))
+}
+
+pub fn rule_matches(rule: &Rule, facts: &LinkFacts) -> bool {
+    CompiledRule::new(rule.clone()).matches(facts)
+}
+
+pub fn condition_matches(condition: &Condition, facts: &LinkFacts) -> bool {
+    CompiledCondition::compile(condition).is_some_and(|compiled| compiled.matches(facts))
+}
+
+/// Lowercases a host pattern and converts internationalised labels to punycode.
+pub fn ascii_host_pattern(pattern: &str) -> String {
+    let host = pattern.trim_end_matches('.').to_lowercase();
+    if host.is_ascii() {
+        return host;
+    }
+    match Host::parse(&host) {
+        Ok(Host::Domain(domain)) => domain,
+        _ => host,
+    }
+}
+
+fn wildcard_pattern(pattern: &str) -> String {
+    let (host, path) = match pattern.find('/') {
+        Some(index) => pattern.split_at(index),
+        None => (pattern, ""),
+    };
+    let host = host
+        .split('.')
+        .map(|label| {
+            if label.contains('*') {
+                label.to_lowercase()
+            } else {
+                ascii_host_pattern(label)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+    format!("{host}{}", path.to_lowercase())
+}
+
+/// Matches `subject` against a glob where `*` stands for any run of characters.
+pub fn wildcard_matches(pattern: &str, subject: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let subject = subject.as_bytes();
+    let (mut p, mut s) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while s < subject.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            backtrack = Some((p, s));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == subject[s] {
+            p += 1;
+            s += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            s = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == b'*')
+}
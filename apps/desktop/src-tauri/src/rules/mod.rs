@@ -0,0 +1,342 @@
+pub mod explain;
+pub mod index;
+pub mod lint;
+pub mod matching;
+pub mod rewrite;
+pub mod schedule;
+pub mod snapshot;
+pub mod stats;
+pub mod url_parts;
+pub mod validation;
+
+use crate::browser_descriptor::{canonical_catalog_id, find_descriptor, BrowserDescriptor};
+use crate::browser_details::{browser_catalog_id, get_profiles_for_browser, resolve_browser_id};
+use crate::domain::models::Rule;
+use serde_json::Value;
+use std::io::ErrorKind;
+use tauri::{async_runtime::RwLock, AppHandle};
+use tauri_plugin_store::{Error as StoreError, StoreExt};
+use uuid::Uuid;
+
+pub use explain::{explain_rules, RuleEvaluation, RuleStatus};
+pub use index::RuleIndex;
+pub use lint::{lint_rules, BrowserInventory, LintCode, RuleLint};
+pub use matching::{find_matching_rule, LinkFacts};
+pub use rewrite::rewrite_url;
+pub use snapshot::{DomainMatchType, DomainRule, FileTypeRule, RuleInput, RuleKind, RulesSnapshot};
+pub use stats::{RuleHit, RuleStats, RuleStatsEntry};
+
+const RULES_STORE: &str = "routing-rules.json";
+const DOMAIN_RULES_KEY: &str = "domainRules";
+const FILE_TYPE_RULES_KEY: &str = "fileTypeRules";
+const STATS_STORE: &str = "rule-stats.json";
+const STATS_KEY: &str = "hits";
+
+/// Backend-owned routing rules; the single source of truth for `routing-rules.json`.
+pub struct RulesState {
+    inner: RwLock<RulesSnapshot>,
+    index: RwLock<RuleIndex>,
+    /// Active workspace; only shared rules and rules in this workspace are indexed.
+    workspace: RwLock<Option<String>>,
+}
+
+impl RulesState {
+    pub fn load(
+        app: &AppHandle,
+        workspace: Option<String>,
+        browsers: &[BrowserDescriptor],
+    ) -> Result<Self, String> {
+        let snapshot = load_rules(app, browsers)?;
+        let index = RuleIndex::build(&snapshot.to_workspace_rules(workspace.as_deref()));
+        Ok(Self {
+            inner: RwLock::new(snapshot),
+            index: RwLock::new(index),
+            workspace: RwLock::new(workspace),
+        })
+    }
+
+    pub async fn snapshot(&self) -> RulesSnapshot {
+        let guard = self.inner.read().await;
+        guard.clone()
+    }
+
+    /// Looks up the highest-priority rule matching the link in the precompiled index.
+    pub async fn find_match(&self, facts: &LinkFacts) -> Option<Rule> {
+        let guard = self.index.read().await;
+        guard.find(facts).cloned()
+    }
+
+    pub async fn upsert(
+        &self,
+        app: &AppHandle,
+        input: RuleInput,
+        browsers: &[BrowserDescriptor],
+    ) -> Result<RulesSnapshot, String> {
+        let input = validate_input(input, browsers)?;
+        {
+            let mut guard = self.inner.write().await;
+            guard.upsert(input);
+        }
+        self.rebuild_index().await;
+        persist_rules(app, &self.inner).await
+    }
+
+    pub async fn delete(&self, app: &AppHandle, id: &str) -> Result<RulesSnapshot, String> {
+        {
+            let mut guard = self.inner.write().await;
+            if !guard.remove(id) {
+                return Err(format!("No rule with id '{id}' exists."));
+            }
+        }
+        self.rebuild_index().await;
+        persist_rules(app, &self.inner).await
+    }
+
+    pub async fn reorder(
+        &self,
+        app: &AppHandle,
+        kind: RuleKind,
+        ids: &[String],
+    ) -> Result<RulesSnapshot, String> {
+        {
+            let mut guard = self.inner.write().await;
+            guard.reorder(kind, ids)?;
+        }
+        self.rebuild_index().await;
+        persist_rules(app, &self.inner).await
+    }
+
+    /// Swaps in a whole rule set, e.g. from an imported configuration.
+    pub async fn replace(
+        &self,
+        app: &AppHandle,
+        snapshot: RulesSnapshot,
+    ) -> Result<RulesSnapshot, String> {
+        {
+            let mut guard = self.inner.write().await;
+            *guard = snapshot;
+        }
+        self.rebuild_index().await;
+        persist_rules(app, &self.inner).await
+    }
+
+    /// Rules that apply in the active workspace, in evaluation form.
+    pub async fn active_rules(&self) -> Vec<Rule> {
+        let workspace = self.workspace.read().await.clone();
+        let guard = self.inner.read().await;
+        guard.to_workspace_rules(workspace.as_deref())
+    }
+
+    /// Switches the workspace whose rules are used for routing.
+    pub async fn set_workspace(&self, workspace: Option<String>) {
+        *self.workspace.write().await = workspace;
+        self.rebuild_index().await;
+    }
+
+    /// Checks the current rules for dead entries and targets missing on this machine.
+    pub async fn lint(&self, browsers: &[BrowserDescriptor]) -> Vec<RuleLint> {
+        let snapshot = self.snapshot().await;
+        lint_rules(&snapshot, &installed_browser_inventory(browsers))
+    }
+
+    async fn rebuild_index(&self) {
+        let rules = self.active_rules().await;
+        let index = RuleIndex::build(&rules);
+        *self.index.write().await = index;
+    }
+}
+
+/// Per-rule hit counters, persisted in `rule-stats.json` apart from the rules themselves.
+pub struct RuleStatsState {
+    inner: RwLock<RuleStats>,
+}
+
+impl RuleStatsState {
+    pub fn load(app: &AppHandle) -> Result<Self, String> {
+        let store = app.store(STATS_STORE).map_err(|err| err.to_string())?;
+
+        if let Err(err) = store.reload() {
+            match err {
+                StoreError::Io(ref io_err) if io_err.kind() == ErrorKind::NotFound => {}
+                other => return Err(other.to_string()),
+            }
+        }
+
+        let stats = match store.get(STATS_KEY) {
+            Some(data) => serde_json::from_value(data).map_err(|err| err.to_string())?,
+            None => RuleStats::default(),
+        };
+        Ok(Self {
+            inner: RwLock::new(stats),
+        })
+    }
+
+    /// Counts a link routed by `rule_id`.
+    pub async fn record(
+        &self,
+        app: &AppHandle,
+        rule_id: &str,
+        url: &str,
+        matched_at: &str,
+    ) -> Result<(), String> {
+        {
+            let mut guard = self.inner.write().await;
+            guard.record(rule_id, url, matched_at);
+        }
+        self.persist(app).await
+    }
+
+    /// Drops the counters of a rule that was deleted.
+    pub async fn forget(&self, app: &AppHandle, rule_id: &str) -> Result<(), String> {
+        let removed = {
+            let mut guard = self.inner.write().await;
+            guard.forget(rule_id)
+        };
+        if removed {
+            self.persist(app).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn report(&self, rules: &RulesSnapshot) -> Vec<RuleStatsEntry> {
+        let guard = self.inner.read().await;
+        guard.report(rules)
+    }
+
+    async fn persist(&self, app: &AppHandle) -> Result<(), String> {
+        let value: Value = {
+            let guard = self.inner.read().await;
+            serde_json::to_value(&*guard).map_err(|err| err.to_string())?
+        };
+
+        let store = app.store(STATS_STORE).map_err(|err| err.to_string())?;
+        store.set(STATS_KEY.to_string(), value);
+        store.save().map_err(|err| err.to_string())
+    }
+}
+
+/// Validates patterns, extensions and browser targets, assigning an id to new rules.
+fn validate_input(input: RuleInput, browsers: &[BrowserDescriptor]) -> Result<RuleInput, String> {
+    let mut input = match input {
+        RuleInput::Domain(rule) => RuleInput::Domain(validation::normalize_domain_rule(rule)?),
+        RuleInput::FileType(rule) => {
+            RuleInput::FileType(validation::normalize_file_type_rule(rule)?)
+        }
+    };
+
+    let (id, browser_id) = match &mut input {
+        RuleInput::Domain(rule) => (&mut rule.id, &mut rule.browser_id),
+        RuleInput::FileType(rule) => (&mut rule.id, &mut rule.browser_id),
+    };
+
+    if let Some(browser_id) = browser_id {
+        validate_browser_id(browsers, browser_id)?;
+        if let Some(canonical) = canonical_catalog_id(browsers, browser_id) {
+            *browser_id = canonical;
+        }
+    }
+
+    if id.trim().is_empty() {
+        *id = Uuid::new_v4().to_string();
+    }
+
+    Ok(input)
+}
+
+/// Ensures a rule targets an installed browser and, when given, one of its profiles.
+pub fn validate_browser_id(browsers: &[BrowserDescriptor], browser_id: &str) -> Result<(), String> {
+    if resolve_browser_id(browsers, browser_id).is_some() {
+        return Ok(());
+    }
+
+    let (base, profile) = browser_id
+        .split_once("__")
+        .unwrap_or((browser_id, "default"));
+    let installed = find_descriptor(browsers, base).is_some();
+
+    if installed {
+        Err(format!(
+            "Profile '{profile}' was not found for browser '{base}'."
+        ))
+    } else {
+        Err(format!("Browser '{base}' is not installed."))
+    }
+}
+
+/// Catalog ids for every installed browser and each profile it reports.
+pub fn installed_browser_inventory(browsers: &[BrowserDescriptor]) -> BrowserInventory {
+    let mut inventory = BrowserInventory::default();
+    for browser in browsers {
+        inventory.ids.push(browser_catalog_id(&browser.id, None));
+
+        let profiles = get_profiles_for_browser(browsers, &browser.display_name);
+        inventory.ids.extend(
+            profiles
+                .iter()
+                .map(|profile| browser_catalog_id(&browser.id, Some(&profile.directory))),
+        );
+        inventory.browsers.push(browser.id.clone());
+    }
+    inventory
+}
+
+fn load_rules(app: &AppHandle, browsers: &[BrowserDescriptor]) -> Result<RulesSnapshot, String> {
+    let store = app.store(RULES_STORE).map_err(|err| err.to_string())?;
+
+    if let Err(err) = store.reload() {
+        match err {
+            StoreError::Io(ref io_err) if io_err.kind() == ErrorKind::NotFound => {}
+            other => return Err(other.to_string()),
+        }
+    }
+
+    let mut snapshot = RulesSnapshot::default();
+
+    if let Some(data) = store.get(DOMAIN_RULES_KEY) {
+        let rules =
+            serde_json::from_value::<Vec<DomainRule>>(data).map_err(|err| err.to_string())?;
+        // Rules written by older frontends were never validated; keep them but canonicalise
+        // whatever parses so evaluation and the UI see the same shape.
+        snapshot.domain_rules = rules
+            .into_iter()
+            .map(|rule| validation::normalize_domain_rule(rule.clone()).unwrap_or(rule))
+            .collect();
+    }
+
+    if let Some(data) = store.get(FILE_TYPE_RULES_KEY) {
+        let rules =
+            serde_json::from_value::<Vec<FileTypeRule>>(data).map_err(|err| err.to_string())?;
+        snapshot.file_type_rules = rules
+            .into_iter()
+            .map(|rule| validation::normalize_file_type_rule(rule.clone()).unwrap_or(rule))
+            .collect();
+    }
+
+    // Rules saved before browsers had stable ids name them by the slug of their display name.
+    snapshot.rewrite_browser_ids(|id| canonical_catalog_id(browsers, id));
+
+    Ok(snapshot)
+}
+
+async fn persist_rules(
+    app: &AppHandle,
+    data: &RwLock<RulesSnapshot>,
+) -> Result<RulesSnapshot, String> {
+    let snapshot = {
+        let guard = data.read().await;
+        guard.clone()
+    };
+
+    let store = app.store(RULES_STORE).map_err(|err| err.to_string())?;
+
+    let domain_rules: Value =
+        serde_json::to_value(&snapshot.domain_rules).map_err(|err| err.to_string())?;
+    let file_type_rules: Value =
+        serde_json::to_value(&snapshot.file_type_rules).map_err(|err| err.to_string())?;
+
+    store.set(DOMAIN_RULES_KEY.to_string(), domain_rules);
+    store.set(FILE_TYPE_RULES_KEY.to_string(), file_type_rules);
+    store.save().map_err(|err| err.to_string())?;
+
+    Ok(snapshot)
+}
@@ -0,0 +1,223 @@
+use super::matching::LinkFacts;
+use super::snapshot::DomainMatchType;
+use crate::domain::models::Rule;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+
+/// Placeholders every rewrite template may use, whatever the rule's pattern.
+pub const BUILTIN_PLACEHOLDERS: [&str; 4] = ["url", "host", "path", "query"];
+
+/// A piece of a wildcard pattern.
+enum Token<'a> {
+    Literal(char),
+    Star,
+    /// A `{name}` segment: matches like `*` and captures under its name.
+    Placeholder(&'a str),
+}
+
+fn tokenize(pattern: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if c == '*' {
+            tokens.push(Token::Star);
+            rest = &rest[1..];
+            continue;
+        }
+        if let Some((name, after)) = placeholder_at(rest).filter(|(name, _)| is_capture_name(name))
+        {
+            tokens.push(Token::Placeholder(name));
+            rest = after;
+            continue;
+        }
+        tokens.push(Token::Literal(c));
+        rest = &rest[c.len_utf8()..];
+    }
+    tokens
+}
+
+/// Splits a leading `{name}` off `text`, returning the name and what follows.
+fn placeholder_at(text: &str) -> Option<(&str, &str)> {
+    let inner = text.strip_prefix('{')?;
+    let end = inner.find('}')?;
+    let name = &inner[..end];
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| (name, &inner[end + 1..]))
+}
+
+/// Pattern placeholders are named; numbers are reserved for positional captures.
+fn is_capture_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// Replaces `{name}` placeholders in a wildcard pattern with `*` for matching.
+pub fn placeholder_glob(pattern: &str) -> String {
+    tokenize(pattern)
+        .into_iter()
+        .map(|token| match token {
+            Token::Literal(c) => c,
+            Token::Star | Token::Placeholder(_) => '*',
+        })
+        .collect()
+}
+
+/// Builds an anchored regex capturing each `*` by position and each `{name}` by name too.
+fn wildcard_regex(pattern: &str) -> Option<Regex> {
+    let mut source = String::from("^");
+    for token in tokenize(pattern) {
+        match token {
+            Token::Literal(c) => source.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            Token::Star => source.push_str("(.*?)"),
+            Token::Placeholder(name) => source.push_str(&format!("(?P<{name}>.*?)")),
+        }
+    }
+    source.push('$');
+    RegexBuilder::new(&source)
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Names a template may reference for a rule with the given pattern.
+fn available_names(match_type: DomainMatchType, pattern: &str) -> Result<Vec<String>, String> {
+    let mut names = BUILTIN_PLACEHOLDERS.map(str::to_string).to_vec();
+    let regex = match match_type {
+        DomainMatchType::Host => return Ok(names),
+        DomainMatchType::Wildcard => {
+            let mut seen = Vec::new();
+            for token in tokenize(pattern) {
+                if let Token::Placeholder(name) = token {
+                    if seen.contains(&name) {
+                        return Err(format!(
+                            "Pattern '{pattern}' uses the placeholder '{{{name}}}' more than once."
+                        ));
+                    }
+                    seen.push(name);
+                }
+            }
+            wildcard_regex(pattern)
+        }
+        DomainMatchType::Regex => Regex::new(pattern).ok(),
+    };
+
+    if let Some(regex) = regex {
+        names.extend((1..regex.captures_len()).map(|index| index.to_string()));
+        names.extend(regex.capture_names().flatten().map(str::to_string));
+    }
+    Ok(names)
+}
+
+/// Checks that a rewrite template only references captures its rule's pattern provides.
+pub fn validate_template(
+    template: &str,
+    match_type: DomainMatchType,
+    pattern: &str,
+) -> Result<(), String> {
+    let names = available_names(match_type, pattern)?;
+    substitute(template, |name| {
+        names.iter().any(|n| n == name).then(String::new)
+    })
+    .map(|_| ())
+}
+
+/// Applies the rule's rewrite template to the link, or returns `None` when it has none.
+pub fn rewrite_url(rule: &Rule, facts: &LinkFacts) -> Option<Result<String, String>> {
+    let template = rule.action.url.trim();
+    if template.is_empty() {
+        return None;
+    }
+
+    let captures = captures(rule, facts);
+    let rewritten = substitute(template, |name| captures.get(name).cloned()).and_then(|url| {
+        let url = if url.contains("://") {
+            url
+        } else {
+            format!("{}://{}", facts.url.scheme(), url)
+        };
+        url::Url::parse(&url)
+            .map(|parsed| parsed.to_string())
+            .map_err(|err| format!("Rewritten URL '{url}' is not valid: {err}"))
+    });
+    Some(rewritten)
+}
+
+/// Built-in values plus whatever the rule's URL condition captured from the link.
+fn captures(rule: &Rule, facts: &LinkFacts) -> HashMap<String, String> {
+    let url = &facts.url;
+    let mut values = HashMap::from([
+        ("url".to_string(), url.to_string()),
+        (
+            "host".to_string(),
+            url.host_str().unwrap_or_default().to_string(),
+        ),
+        ("path".to_string(), url.path().to_string()),
+        (
+            "query".to_string(),
+            url.query().unwrap_or_default().to_string(),
+        ),
+    ]);
+
+    let Some(condition) = rule.conditions.iter().find(|c| c.fact == "url") else {
+        return values;
+    };
+    let pattern = condition.value.trim();
+    let (regex, subject) = match condition.operator.as_str() {
+        "wildcard" => {
+            let host = url.host_str().unwrap_or_default().trim_end_matches('.');
+            let subject = if pattern.contains('/') {
+                format!("{host}{}", url.path())
+            } else {
+                host.to_string()
+            };
+            (wildcard_regex(pattern), subject)
+        }
+        "regex" => (
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .ok(),
+            url.to_string(),
+        ),
+        _ => (None, String::new()),
+    };
+
+    let Some(regex) = regex else {
+        return values;
+    };
+    let Some(found) = regex.captures(&subject) else {
+        return values;
+    };
+    for (index, name) in regex.capture_names().enumerate().skip(1) {
+        let Some(value) = found.get(index) else {
+            continue;
+        };
+        values.insert(index.to_string(), value.as_str().to_string());
+        if let Some(name) = name {
+            values.insert(name.to_string(), value.as_str().to_string());
+        }
+    }
+    values
+}
+
+/// Replaces every `{name}` in `template`, failing on names `lookup` does not know.
+fn substitute(template: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some((name, after)) = placeholder_at(&rest[start..]) else {
+            output.push('{');
+            rest = &rest[start + 1..];
+            continue;
+        };
+        let value = lookup(name).ok_or_else(|| {
+            format!("Rewrite template references unknown placeholder '{{{name}}}'.")
+        })?;
+        output.push_str(&value);
+        rest = after;
+    }
+    output.push_str(rest);
+    Ok(output)
+}
@@ -0,0 +1,143 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+/// Schedule facts a rule condition can test against the local clock.
+pub const SCHEDULE_FACTS: [&str; 3] = ["weekday", "time", "date"];
+
+/// A parsed schedule condition, evaluated against local wall-clock time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    /// `weekday in mon-fri` or `weekday in sat,sun`.
+    Weekdays([bool; 7]),
+    /// `time between 09:00-17:30`; the end is exclusive and windows may wrap past midnight.
+    TimeWindow { start: NaiveTime, end: NaiveTime },
+    /// `date between 2026-01-01..2026-03-31`; both ends are inclusive and either may be omitted.
+    DateRange {
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    },
+}
+
+impl Schedule {
+    pub fn parse(fact: &str, operator: &str, value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        match (fact, operator) {
+            ("weekday", "in") => parse_weekdays(value),
+            ("time", "between") => parse_time_window(value),
+            ("date", "between") => parse_date_range(value),
+            ("weekday", _) => Err(format!(
+                "Unsupported operator '{operator}' for 'weekday'; expected 'in'."
+            )),
+            ("time" | "date", _) => Err(format!(
+                "Unsupported operator '{operator}' for '{fact}'; expected 'between'."
+            )),
+            _ => Err(format!("Unsupported schedule fact '{fact}'.")),
+        }
+    }
+
+    /// The part of `now` this schedule looks at, formatted the way conditions are written.
+    pub fn subject(&self, now: NaiveDateTime) -> String {
+        match self {
+            Schedule::Weekdays(_) => now.format("%a").to_string().to_lowercase(),
+            Schedule::TimeWindow { .. } => now.format("%H:%M").to_string(),
+            Schedule::DateRange { .. } => now.format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    pub fn matches(&self, now: NaiveDateTime) -> bool {
+        match self {
+            Schedule::Weekdays(days) => days[now.weekday().num_days_from_monday() as usize],
+            Schedule::TimeWindow { start, end } => {
+                let time = now.time();
+                if start < end {
+                    *start <= time && time < *end
+                } else {
+                    *start <= time || time < *end
+                }
+            }
+            Schedule::DateRange { start, end } => {
+                let date = now.date();
+                start.is_none_or(|start| start <= date) && end.is_none_or(|end| date <= end)
+            }
+        }
+    }
+}
+
+fn parse_weekdays(value: &str) -> Result<Schedule, String> {
+    let mut days = [false; 7];
+    for part in value.split(',').map(str::trim) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (parse_weekday(first)?, parse_weekday(last)?),
+            None => {
+                let day = parse_weekday(part)?;
+                (day, day)
+            }
+        };
+
+        let mut day = first;
+        loop {
+            days[day.num_days_from_monday() as usize] = true;
+            if day == last {
+                break;
+            }
+            day = day.succ();
+        }
+    }
+    Ok(Schedule::Weekdays(days))
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| format!("Unknown weekday '{}'.", value.trim()))
+}
+
+fn parse_time_window(value: &str) -> Result<Schedule, String> {
+    let (start, end) = value
+        .split_once('-')
+        .ok_or_else(|| format!("Time window '{value}' must look like '09:00-17:30'."))?;
+    let start = parse_time(start)?;
+    let end = parse_time(end)?;
+    if start == end {
+        return Err(format!("Time window '{value}' is empty."));
+    }
+    Ok(Schedule::TimeWindow { start, end })
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    let value = value.trim();
+    if value == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| format!("Invalid time '{value}'."))
+}
+
+fn parse_date_range(value: &str) -> Result<Schedule, String> {
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) => (parse_date(start)?, parse_date(end)?),
+        None => {
+            let day = parse_date(value)?;
+            if day.is_none() {
+                return Err("Date range cannot be empty.".to_string());
+            }
+            (day, day)
+        }
+    };
+
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err(format!("Date range '{value}' ends before it starts."));
+        }
+    }
+    Ok(Schedule::DateRange { start, end })
+}
+
+fn parse_date(value: &str) -> Result<Option<NaiveDate>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(Some)
+        .map_err(|_| format!("Invalid date '{value}'; expected YYYY-MM-DD."))
+}
@@ -0,0 +1,320 @@
+use crate::domain::models::{Action, Condition, Rule, RulePolicy};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DomainMatchType {
+    Host,
+    Wildcard,
+    Regex,
+}
+
+impl DomainMatchType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DomainMatchType::Host => "host",
+            DomainMatchType::Wildcard => "wildcard",
+            DomainMatchType::Regex => "regex",
+        }
+    }
+
+    pub fn parse(operator: &str) -> Option<Self> {
+        match operator {
+            "host" => Some(DomainMatchType::Host),
+            "wildcard" => Some(DomainMatchType::Wildcard),
+            "regex" => Some(DomainMatchType::Regex),
+            _ => None,
+        }
+    }
+
+    /// Mirrors the frontend's inference for rules saved without an explicit match type.
+    pub fn infer(pattern: &str) -> Self {
+        if pattern.contains('*') {
+            DomainMatchType::Wildcard
+        } else if pattern.starts_with('^') || pattern.ends_with('$') {
+            DomainMatchType::Regex
+        } else {
+            DomainMatchType::Host
+        }
+    }
+}
+
+/// Domain rule as persisted in `routing-rules.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainRule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default)]
+    pub match_type: Option<DomainMatchType>,
+    #[serde(default)]
+    pub browser_id: Option<String>,
+    #[serde(default)]
+    pub browser_label: String,
+    #[serde(default)]
+    pub policy: RulePolicy,
+    #[serde(default = "default_latency")]
+    pub latency: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Workspace the rule belongs to; rules without one apply in every workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Template the URL is rewritten to before launch, e.g. `new.example.com/{key}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite: Option<String>,
+    /// Extra conditions, e.g. on the source application, that must all hold alongside the pattern.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+/// File type rule as persisted in `routing-rules.json`.
+///
+/// These are also the rules that route local `file:` paths, which domain rules never match.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTypeRule {
+    #[serde(default)]
+    pub id: String,
+    pub extension: String,
+    #[serde(default)]
+    pub browser_id: Option<String>,
+    #[serde(default)]
+    pub browser_label: String,
+    #[serde(default)]
+    pub policy: RulePolicy,
+    /// Workspace the rule belongs to; rules without one apply in every workspace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+}
+
+fn default_latency() -> String {
+    "Auto".to_string()
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl DomainRule {
+    pub fn effective_pattern(&self) -> &str {
+        let pattern = self.pattern.trim();
+        if pattern.is_empty() {
+            self.domain.as_deref().unwrap_or_default().trim()
+        } else {
+            pattern
+        }
+    }
+
+    pub fn effective_match_type(&self) -> DomainMatchType {
+        self.match_type
+            .unwrap_or_else(|| DomainMatchType::infer(self.effective_pattern()))
+    }
+
+    /// Converts the stored rule into the engine model; list position becomes the priority.
+    pub fn to_rule(&self, priority: i32) -> Rule {
+        let pattern = self.effective_pattern();
+        let mut conditions = vec![Condition {
+            fact: "url".to_string(),
+            operator: self.effective_match_type().as_str().to_string(),
+            value: pattern.to_string(),
+        }];
+        conditions.extend(self.conditions.iter().cloned());
+
+        Rule {
+            id: self.id.clone(),
+            rule_name: pattern.to_string(),
+            priority,
+            conditions,
+            action: Action {
+                profile: String::new(),
+                browser: self.browser_id.clone().unwrap_or_default(),
+                url: self.rewrite.clone().unwrap_or_default(),
+                policy: self.policy,
+            },
+            enabled: self.enabled,
+        }
+    }
+}
+
+impl FileTypeRule {
+    /// Converts the stored rule into an engine rule matching on the linked file's extension.
+    pub fn to_rule(&self, priority: i32) -> Rule {
+        Rule {
+            id: self.id.clone(),
+            rule_name: self.extension.clone(),
+            priority,
+            conditions: vec![Condition {
+                fact: "extension".to_string(),
+                operator: "equals".to_string(),
+                value: self.extension.clone(),
+            }],
+            action: Action {
+                profile: String::new(),
+                browser: self.browser_id.clone().unwrap_or_default(),
+                url: String::new(),
+                policy: self.policy,
+            },
+            enabled: true,
+        }
+    }
+}
+
+/// Whether a rule tagged with `rule_workspace` applies while `active` is the active workspace.
+pub fn applies_in(rule_workspace: Option<&str>, active: Option<&str>) -> bool {
+    rule_workspace.is_none() || rule_workspace == active
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleKind {
+    Domain,
+    FileType,
+}
+
+/// A rule submitted for insertion or replacement, tagged with its kind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum RuleInput {
+    Domain(DomainRule),
+    FileType(FileTypeRule),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesSnapshot {
+    #[serde(default)]
+    pub domain_rules: Vec<DomainRule>,
+    #[serde(default)]
+    pub file_type_rules: Vec<FileTypeRule>,
+}
+
+impl RulesSnapshot {
+    /// All rules in evaluation form, prioritised by their position in each list.
+    ///
+    /// File type rules take precedence over domain rules: they get negative priorities so a
+    /// PDF opens in the PDF browser whichever site serves it, while domain rules keep their
+    /// list position as priority.
+    pub fn to_rules(&self) -> Vec<Rule> {
+        let file_types = self.file_type_rules.len() as i32;
+        let file_type_rules = self
+            .file_type_rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| rule.to_rule(index as i32 - file_types));
+        file_type_rules.chain(self.to_domain_rules()).collect()
+    }
+
+    /// The rules that apply while `workspace` is active, in evaluation form.
+    ///
+    /// Rules without a workspace always apply; the others only in their own workspace.
+    pub fn to_workspace_rules(&self, workspace: Option<&str>) -> Vec<Rule> {
+        let workspaces = self
+            .file_type_rules
+            .iter()
+            .map(|rule| rule.workspace.as_deref())
+            .chain(self.domain_rules.iter().map(|rule| rule.workspace.as_deref()));
+        self.to_rules()
+            .into_iter()
+            .zip(workspaces)
+            .filter(|(_, rule_workspace)| applies_in(*rule_workspace, workspace))
+            .map(|(rule, _)| rule)
+            .collect()
+    }
+
+    /// Domain rules alone in evaluation form.
+    pub fn to_domain_rules(&self) -> Vec<Rule> {
+        self.domain_rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| rule.to_rule(index as i32))
+            .collect()
+    }
+
+    /// Replaces the rule sharing the input's id in place, or appends it when new.
+    pub fn upsert(&mut self, input: RuleInput) {
+        match input {
+            RuleInput::Domain(rule) => {
+                match self.domain_rules.iter_mut().find(|r| r.id == rule.id) {
+                    Some(existing) => *existing = rule,
+                    None => self.domain_rules.push(rule),
+                }
+            }
+            RuleInput::FileType(rule) => {
+                match self.file_type_rules.iter_mut().find(|r| r.id == rule.id) {
+                    Some(existing) => *existing = rule,
+                    None => self.file_type_rules.push(rule),
+                }
+            }
+        }
+    }
+
+    /// Replaces each rule's browser id with what `rewrite` returns for it, when it returns one.
+    pub fn rewrite_browser_ids(&mut self, rewrite: impl Fn(&str) -> Option<String>) {
+        let browser_ids = self
+            .domain_rules
+            .iter_mut()
+            .map(|rule| &mut rule.browser_id)
+            .chain(
+                self.file_type_rules
+                    .iter_mut()
+                    .map(|rule| &mut rule.browser_id),
+            );
+        for browser_id in browser_ids.flatten() {
+            if let Some(rewritten) = rewrite(browser_id) {
+                *browser_id = rewritten;
+            }
+        }
+    }
+
+    /// Removes the rule with the given id from either list, reporting whether it existed.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.domain_rules.len() + self.file_type_rules.len();
+        self.domain_rules.retain(|rule| rule.id != id);
+        self.file_type_rules.retain(|rule| rule.id != id);
+        before != self.domain_rules.len() + self.file_type_rules.len()
+    }
+
+    /// Reorders one rule list; `ids` must name every rule of that kind exactly once.
+    pub fn reorder(&mut self, kind: RuleKind, ids: &[String]) -> Result<(), String> {
+        match kind {
+            RuleKind::Domain => reorder_by_id(&mut self.domain_rules, ids, |r| &r.id),
+            RuleKind::FileType => reorder_by_id(&mut self.file_type_rules, ids, |r| &r.id),
+        }
+    }
+}
+
+fn reorder_by_id<T>(
+    items: &mut Vec<T>,
+    ids: &[String],
+    id_of: impl Fn(&T) -> &String,
+) -> Result<(), String> {
+    if ids.len() != items.len() {
+        return Err(format!(
+            "Expected {} rule ids but received {}.",
+            items.len(),
+            ids.len()
+        ));
+    }
+
+    let mut positions = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        let id = id_of(item);
+        match ids.iter().position(|candidate| candidate == id) {
+            Some(position) => positions.push(position),
+            None => return Err(format!("Rule '{id}' is missing from the new order.")),
+        }
+    }
+
+    let mut keyed = positions
+        .into_iter()
+        .zip(items.drain(..))
+        .collect::<Vec<_>>();
+    keyed.sort_by_key(|(position, _)| *position);
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use super::snapshot::{RuleKind, RulesSnapshot};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How often a rule has routed a link, and the last link it caught.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleHit {
+    #[serde(default)]
+    pub count: u64,
+    #[serde(default)]
+    pub last_matched_at: Option<String>,
+    #[serde(default)]
+    pub last_matched_url: Option<String>,
+}
+
+/// Hit counters keyed by rule id, as persisted in `rule-stats.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuleStats {
+    hits: BTreeMap<String, RuleHit>,
+}
+
+/// A rule's counters for the Rules page; rules that never matched have a count of zero.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStatsEntry {
+    pub rule_id: String,
+    pub kind: RuleKind,
+    #[serde(flatten)]
+    pub hit: RuleHit,
+}
+
+impl RuleStats {
+    pub fn get(&self, rule_id: &str) -> Option<&RuleHit> {
+        self.hits.get(rule_id)
+    }
+
+    pub fn record(&mut self, rule_id: &str, url: &str, matched_at: &str) {
+        let hit = self.hits.entry(rule_id.to_string()).or_default();
+        hit.count = hit.count.saturating_add(1);
+        hit.last_matched_at = Some(matched_at.to_string());
+        hit.last_matched_url = Some(url.to_string());
+    }
+
+    /// Drops the counters of a deleted rule; returns whether there were any.
+    pub fn forget(&mut self, rule_id: &str) -> bool {
+        self.hits.remove(rule_id).is_some()
+    }
+
+    /// Counters for every rule in `snapshot`, in rule order, ignoring rules that no longer exist.
+    pub fn report(&self, snapshot: &RulesSnapshot) -> Vec<RuleStatsEntry> {
+        let domain = snapshot
+            .domain_rules
+            .iter()
+            .map(|rule| (rule.id.as_str(), RuleKind::Domain));
+        let file_types = snapshot
+            .file_type_rules
+            .iter()
+            .map(|rule| (rule.id.as_str(), RuleKind::FileType));
+
+        domain
+            .chain(file_types)
+            .map(|(id, kind)| RuleStatsEntry {
+                rule_id: id.to_string(),
+                kind,
+                hit: self.hits.get(id).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
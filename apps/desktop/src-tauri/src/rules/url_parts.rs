@@ -0,0 +1,163 @@
+use super::matching::wildcard_matches;
+use regex::{Regex, RegexBuilder};
+use url::Url;
+
+/// Parts of the URL a rule may add conditions on, besides its pattern.
+pub const URL_PART_FACTS: [&str; 4] = ["path", "query", "port", "scheme"];
+
+/// A parsed condition on the path, a query parameter, the port or the scheme.
+#[derive(Debug, Clone)]
+pub enum UrlPart {
+    /// Matches the path itself or anything below it, segment by segment.
+    PathPrefix(String),
+    PathGlob(String),
+    PathRegex(Regex),
+    QueryPresent(String),
+    QueryEquals {
+        name: String,
+        value: String,
+    },
+    QueryRegex {
+        name: String,
+        regex: Regex,
+    },
+    /// Compared against the explicit port or the scheme's default.
+    Port(u16),
+    Scheme(String),
+}
+
+impl UrlPart {
+    /// Parses a `path`, `query`, `port` or `scheme` condition.
+    ///
+    /// Query conditions name the parameter in the value: `ref` for `present`, `ref=mail`
+    /// for `equals` and `ref=^news` for `regex`.
+    pub fn parse(fact: &str, operator: &str, value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("Condition on '{fact}' needs a value."));
+        }
+
+        match (fact, operator) {
+            ("path", "prefix") => Ok(UrlPart::PathPrefix(rooted(value))),
+            ("path", "glob") => Ok(UrlPart::PathGlob(rooted(value))),
+            ("path", "regex") => compile_regex(value).map(UrlPart::PathRegex),
+            ("path", _) => Err(unsupported(fact, operator, "prefix, glob or regex")),
+            ("query", "present") => Ok(UrlPart::QueryPresent(value.to_string())),
+            ("query", "equals") => {
+                let (name, expected) = parameter(value)?;
+                Ok(UrlPart::QueryEquals {
+                    name,
+                    value: expected.to_string(),
+                })
+            }
+            ("query", "regex") => {
+                let (name, pattern) = parameter(value)?;
+                Ok(UrlPart::QueryRegex {
+                    name,
+                    regex: compile_regex(pattern)?,
+                })
+            }
+            ("query", _) => Err(unsupported(fact, operator, "present, equals or regex")),
+            ("port", "equals") => value
+                .parse::<u16>()
+                .ok()
+                .filter(|port| *port > 0)
+                .map(UrlPart::Port)
+                .ok_or_else(|| {
+                    format!("Invalid port '{value}'; expected a number from 1 to 65535.")
+                }),
+            ("port", _) => Err(unsupported(fact, operator, "equals")),
+            ("scheme", "equals") => {
+                let scheme = value.trim_end_matches("://").trim_end_matches(':');
+                let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+                if valid {
+                    Ok(UrlPart::Scheme(scheme.to_ascii_lowercase()))
+                } else {
+                    Err(format!("Invalid scheme '{value}'."))
+                }
+            }
+            ("scheme", _) => Err(unsupported(fact, operator, "equals")),
+            _ => Err(format!("Unsupported URL condition fact '{fact}'.")),
+        }
+    }
+
+    /// The part of the URL this condition looks at, for explanations.
+    pub fn subject(&self, url: &Url) -> String {
+        match self {
+            UrlPart::PathPrefix(_) | UrlPart::PathGlob(_) | UrlPart::PathRegex(_) => {
+                url.path().to_string()
+            }
+            UrlPart::QueryPresent(name)
+            | UrlPart::QueryEquals { name, .. }
+            | UrlPart::QueryRegex { name, .. } => url
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(key, value)| format!("{key}={value}"))
+                .unwrap_or_default(),
+            UrlPart::Port(_) => url
+                .port_or_known_default()
+                .map(|port| port.to_string())
+                .unwrap_or_default(),
+            UrlPart::Scheme(_) => url.scheme().to_string(),
+        }
+    }
+
+    pub fn matches(&self, url: &Url) -> bool {
+        match self {
+            UrlPart::PathPrefix(prefix) => {
+                let path = url.path().to_lowercase();
+                match path.strip_prefix(prefix.as_str()) {
+                    Some(rest) => rest.is_empty() || prefix.ends_with('/') || rest.starts_with('/'),
+                    None => false,
+                }
+            }
+            UrlPart::PathGlob(pattern) => wildcard_matches(pattern, &url.path().to_lowercase()),
+            UrlPart::PathRegex(regex) => regex.is_match(url.path()),
+            UrlPart::QueryPresent(name) => url.query_pairs().any(|(key, _)| key == *name),
+            UrlPart::QueryEquals { name, value } => url
+                .query_pairs()
+                .any(|(key, actual)| key == *name && actual == *value),
+            UrlPart::QueryRegex { name, regex } => url
+                .query_pairs()
+                .any(|(key, actual)| key == *name && regex.is_match(&actual)),
+            UrlPart::Port(port) => url.port_or_known_default() == Some(*port),
+            UrlPart::Scheme(scheme) => url.scheme() == scheme,
+        }
+    }
+}
+
+/// Lowercases a path pattern and makes sure it starts at the root.
+fn rooted(value: &str) -> String {
+    let value = value.to_lowercase();
+    if value.starts_with('/') || value.starts_with('*') {
+        value
+    } else {
+        format!("/{value}")
+    }
+}
+
+/// Splits `name=value`; the value may be empty to match a parameter given without one.
+fn parameter(value: &str) -> Result<(String, &str), String> {
+    match value.split_once('=') {
+        Some((name, expected)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), expected.trim()))
+        }
+        _ => Err(format!(
+            "Query condition '{value}' must be written as name=value."
+        )),
+    }
+}
+
+fn compile_regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|err| format!("Invalid regular expression '{pattern}': {err}"))
+}
+
+fn unsupported(fact: &str, operator: &str, expected: &str) -> String {
+    format!("Unsupported operator '{operator}' for '{fact}'; expected {expected}.")
+}
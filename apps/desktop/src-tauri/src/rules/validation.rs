@@ -0,0 +1,214 @@
+use super::rewrite::{placeholder_glob, validate_template};
+use super::schedule::{Schedule, SCHEDULE_FACTS};
+use super::snapshot::{DomainMatchType, DomainRule, FileTypeRule};
+use super::url_parts::{UrlPart, URL_PART_FACTS};
+use crate::domain::models::Condition;
+use regex::Regex;
+use url::Host;
+
+/// Checks that a domain rule pattern is well formed for its match type.
+pub fn validate_pattern(match_type: DomainMatchType, pattern: &str) -> Result<(), String> {
+    if pattern.is_empty() {
+        return Err("Rule pattern cannot be empty.".to_string());
+    }
+
+    if pattern.chars().any(char::is_whitespace) && match_type != DomainMatchType::Regex {
+        return Err(format!("Pattern '{pattern}' must not contain whitespace."));
+    }
+
+    match match_type {
+        DomainMatchType::Host => {
+            if pattern.contains('*') {
+                return Err(format!(
+                    "Host pattern '{pattern}' contains '*'; use a wildcard rule instead."
+                ));
+            }
+            validate_host(pattern, pattern)
+        }
+        DomainMatchType::Wildcard => {
+            let pattern = placeholder_glob(pattern);
+            let pattern = pattern.as_str();
+            if pattern.contains("://") {
+                return Err(format!(
+                    "Wildcard pattern '{pattern}' must not include a scheme."
+                ));
+            }
+            let host = pattern.split('/').next().unwrap_or_default();
+            if host.trim_matches('*').is_empty() && !pattern.contains('/') {
+                return Err(format!(
+                    "Wildcard pattern '{pattern}' must contain more than '*'."
+                ));
+            }
+            if host.chars().all(|c| c == '*') {
+                return Ok(());
+            }
+            validate_host(&host.replace('*', "x"), pattern)
+        }
+        DomainMatchType::Regex => Regex::new(pattern)
+            .map(|_| ())
+            .map_err(|err| format!("Invalid regular expression '{pattern}': {err}")),
+    }
+}
+
+fn validate_host(host: &str, pattern: &str) -> Result<(), String> {
+    if host.contains("://") || host.contains('/') {
+        return Err(format!(
+            "Host pattern '{pattern}' must be a bare host name without scheme or path."
+        ));
+    }
+
+    Host::parse(host)
+        .map(|_| ())
+        .map_err(|err| format!("Invalid host in pattern '{pattern}': {err}"))
+}
+
+/// Facts a domain rule may add conditions on, besides its URL pattern.
+pub const SOURCE_FACTS: [&str; 2] = ["source_app", "source_context"];
+
+/// Operators supported for source facts.
+pub const TEXT_OPERATORS: [&str; 3] = ["equals", "contains", "regex"];
+
+/// Checks an extra rule condition and returns it trimmed.
+pub fn normalize_condition(condition: Condition) -> Result<Condition, String> {
+    let fact = condition.fact.trim().to_string();
+    let operator = condition.operator.trim().to_ascii_lowercase();
+    let value = condition.value.trim().to_string();
+
+    if SCHEDULE_FACTS.contains(&fact.as_str()) {
+        Schedule::parse(&fact, &operator, &value)?;
+        return Ok(Condition {
+            fact,
+            operator,
+            value,
+        });
+    }
+
+    if URL_PART_FACTS.contains(&fact.as_str()) {
+        UrlPart::parse(&fact, &operator, &value)?;
+        return Ok(Condition {
+            fact,
+            operator,
+            value,
+        });
+    }
+
+    if fact == "extension" {
+        if operator != "equals" {
+            return Err(format!(
+                "Unsupported operator '{operator}' for 'extension'; expected equals."
+            ));
+        }
+        return Ok(Condition {
+            value: normalize_extension(&value)?,
+            fact,
+            operator,
+        });
+    }
+
+    if !SOURCE_FACTS.contains(&fact.as_str()) {
+        return Err(format!(
+            "Unsupported condition fact '{fact}'; expected one of {}, {}, {}, extension.",
+            SOURCE_FACTS.join(", "),
+            SCHEDULE_FACTS.join(", "),
+            URL_PART_FACTS.join(", ")
+        ));
+    }
+    if !TEXT_OPERATORS.contains(&operator.as_str()) {
+        return Err(format!(
+            "Unsupported operator '{operator}' for '{fact}'; expected one of {}.",
+            TEXT_OPERATORS.join(", ")
+        ));
+    }
+    if value.is_empty() {
+        return Err(format!("Condition on '{fact}' needs a value."));
+    }
+    if operator == "regex" {
+        Regex::new(&value).map_err(|err| format!("Invalid regular expression '{value}': {err}"))?;
+    }
+
+    Ok(Condition {
+        fact,
+        operator,
+        value,
+    })
+}
+
+/// Checks any engine condition, including the URL condition a domain rule compiles to.
+pub fn validate_condition(condition: &Condition) -> Result<(), String> {
+    if condition.fact == "url" {
+        let match_type = DomainMatchType::parse(&condition.operator).ok_or_else(|| {
+            format!(
+                "Unsupported operator '{}' for 'url'; expected host, wildcard or regex.",
+                condition.operator
+            )
+        })?;
+        return validate_pattern(match_type, condition.value.trim());
+    }
+    normalize_condition(condition.clone()).map(|_| ())
+}
+
+/// Normalizes a file extension to its `.ext` lowercase form.
+pub fn normalize_extension(extension: &str) -> Result<String, String> {
+    let trimmed = extension
+        .trim()
+        .trim_start_matches('*')
+        .trim_start_matches('.');
+    if trimmed.is_empty() {
+        return Err("File extension cannot be empty.".to_string());
+    }
+
+    let valid = trimmed
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid || trimmed.ends_with('.') {
+        return Err(format!("Invalid file extension '{extension}'."));
+    }
+
+    Ok(format!(".{}", trimmed.to_ascii_lowercase()))
+}
+
+/// Validates a domain rule and returns it in canonical stored form.
+pub fn normalize_domain_rule(mut rule: DomainRule) -> Result<DomainRule, String> {
+    let pattern = rule.effective_pattern().to_string();
+    let match_type = rule.effective_match_type();
+    validate_pattern(match_type, &pattern)?;
+
+    rule.pattern = pattern;
+    rule.domain = None;
+    rule.match_type = Some(match_type);
+    rule.browser_id = normalize_browser_id(rule.browser_id);
+    rule.workspace = normalize_workspace(rule.workspace);
+    rule.rewrite = rule
+        .rewrite
+        .map(|template| template.trim().to_string())
+        .filter(|template| !template.is_empty());
+    if let Some(template) = &rule.rewrite {
+        validate_template(template, match_type, &rule.pattern)?;
+    }
+    rule.conditions = rule
+        .conditions
+        .into_iter()
+        .map(normalize_condition)
+        .collect::<Result<_, _>>()?;
+    Ok(rule)
+}
+
+/// Validates a file type rule and returns it in canonical stored form.
+pub fn normalize_file_type_rule(mut rule: FileTypeRule) -> Result<FileTypeRule, String> {
+    rule.extension = normalize_extension(&rule.extension)?;
+    rule.browser_id = normalize_browser_id(rule.browser_id);
+    rule.workspace = normalize_workspace(rule.workspace);
+    Ok(rule)
+}
+
+fn normalize_browser_id(browser_id: Option<String>) -> Option<String> {
+    browser_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+fn normalize_workspace(workspace: Option<String>) -> Option<String> {
+    workspace
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
@@ -0,0 +1,240 @@
+use crate::browser_descriptor::{find_icon, stable_id, BrowserDescriptor, BrowserFamily};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a sandboxed browser was installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxKind {
+    Flatpak,
+    Snap,
+}
+
+impl SandboxKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SandboxKind::Flatpak => "Flatpak",
+            SandboxKind::Snap => "Snap",
+        }
+    }
+}
+
+/// Where a sandboxed browser keeps its profiles, relative to its sandbox home.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileStore {
+    /// Path of the Chromium `Local State` file.
+    Chromium(&'static str),
+    /// Directory holding the Firefox profile folders.
+    Gecko(&'static str),
+}
+
+/// A browser package that lives in a Flatpak or Snap sandbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SandboxedBrowser {
+    pub kind: SandboxKind,
+    /// Flatpak application id or snap name.
+    pub app_id: &'static str,
+    /// Name of the browser itself, which picks its profile command-line arguments.
+    pub browser: &'static str,
+    pub profiles: ProfileStore,
+}
+
+/// Sandboxed browser packages whose profile layout is known.
+pub const KNOWN_SANDBOXED_BROWSERS: [SandboxedBrowser; 8] = [
+    SandboxedBrowser {
+        kind: SandboxKind::Flatpak,
+        app_id: "org.mozilla.firefox",
+        browser: "Firefox",
+        profiles: ProfileStore::Gecko(".mozilla/firefox"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Flatpak,
+        app_id: "com.google.Chrome",
+        browser: "Chrome",
+        profiles: ProfileStore::Chromium("config/google-chrome/Local State"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Flatpak,
+        app_id: "com.brave.Browser",
+        browser: "Brave",
+        profiles: ProfileStore::Chromium("config/BraveSoftware/Brave-Browser/Local State"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Flatpak,
+        app_id: "org.chromium.Chromium",
+        browser: "Chromium",
+        profiles: ProfileStore::Chromium("config/chromium/Local State"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Flatpak,
+        app_id: "com.microsoft.Edge",
+        browser: "Edge",
+        profiles: ProfileStore::Chromium("config/microsoft-edge/Local State"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Snap,
+        app_id: "firefox",
+        browser: "Firefox",
+        profiles: ProfileStore::Gecko("common/.mozilla/firefox"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Snap,
+        app_id: "chromium",
+        browser: "Chromium",
+        profiles: ProfileStore::Chromium("common/chromium/Local State"),
+    },
+    SandboxedBrowser {
+        kind: SandboxKind::Snap,
+        app_id: "brave",
+        browser: "Brave",
+        profiles: ProfileStore::Chromium("current/.config/BraveSoftware/Brave-Browser/Local State"),
+    },
+];
+
+/// Directory that holds the snap command wrappers.
+const SNAP_BIN: &str = "/snap/bin";
+/// Directory snaps are mounted under.
+pub const SNAP_ROOT: &str = "/snap";
+
+impl SandboxedBrowser {
+    /// Name shown for the browser, e.g. `Firefox (Flatpak)`.
+    pub fn display_name(&self) -> String {
+        format!("{} ({})", self.browser, self.kind.label())
+    }
+
+    /// The program and arguments that start the browser; the link is appended after them.
+    pub fn launch_command(&self) -> (PathBuf, Vec<String>) {
+        match self.kind {
+            SandboxKind::Flatpak => {
+                let flatpak = which::which("flatpak").unwrap_or_else(|_| PathBuf::from("flatpak"));
+                (flatpak, vec!["run".to_string(), self.app_id.to_string()])
+            }
+            SandboxKind::Snap => (Path::new(SNAP_BIN).join(self.app_id), Vec::new()),
+        }
+    }
+
+    /// Where the profiles live for a user whose home directory is `home`.
+    pub fn profile_path(&self, home: &Path) -> PathBuf {
+        let sandbox_home = match self.kind {
+            SandboxKind::Flatpak => home.join(".var/app").join(self.app_id),
+            SandboxKind::Snap => home.join("snap").join(self.app_id),
+        };
+        match self.profiles {
+            ProfileStore::Chromium(path) | ProfileStore::Gecko(path) => sandbox_home.join(path),
+        }
+    }
+
+    /// Id that tells this install apart from the same browser installed natively.
+    pub fn id(&self) -> String {
+        stable_id(&[&self.kind.label().to_lowercase(), self.app_id])
+    }
+
+    pub fn family(&self) -> BrowserFamily {
+        match self.profiles {
+            ProfileStore::Chromium(_) => BrowserFamily::Chromium,
+            ProfileStore::Gecko(_) => BrowserFamily::Gecko,
+        }
+    }
+
+    /// Version from the snap metadata or the Flatpak AppStream release list.
+    pub fn version_in(&self, flatpak_roots: &[PathBuf], snap_root: &Path) -> Option<String> {
+        match self.kind {
+            SandboxKind::Flatpak => flatpak_roots.iter().find_map(|root| {
+                let metainfo = root
+                    .join("app")
+                    .join(self.app_id)
+                    .join("current/active/files/share/metainfo");
+                ["metainfo.xml", "appdata.xml"].iter().find_map(|ext| {
+                    let path = metainfo.join(format!("{}.{ext}", self.app_id));
+                    release_version(&fs::read_to_string(path).ok()?)
+                })
+            }),
+            SandboxKind::Snap => {
+                let path = snap_root.join(self.app_id).join("current/meta/snap.yaml");
+                fs::read_to_string(path).ok()?.lines().find_map(|line| {
+                    let version = line.strip_prefix("version:")?.trim();
+                    Some(version.trim_matches(['\'', '"']).to_string())
+                        .filter(|version| !version.is_empty())
+                })
+            }
+        }
+    }
+
+    /// Icon exported by the Flatpak app or shipped in the snap's `meta/gui` directory.
+    pub fn icon_in(&self, flatpak_roots: &[PathBuf], snap_root: &Path) -> Option<PathBuf> {
+        match self.kind {
+            SandboxKind::Flatpak => {
+                let exports = flatpak_roots
+                    .iter()
+                    .map(|root| root.join("exports/share"))
+                    .collect::<Vec<_>>();
+                find_icon(self.app_id, &exports)
+            }
+            SandboxKind::Snap => {
+                let gui = snap_root.join(self.app_id).join("current/meta/gui");
+                [format!("{}.png", self.app_id), "default256.png".to_string()]
+                    .into_iter()
+                    .map(|name| gui.join(name))
+                    .find(|path| path.is_file())
+            }
+        }
+    }
+
+    fn is_installed_in(&self, flatpak_roots: &[PathBuf], snap_root: &Path) -> bool {
+        match self.kind {
+            SandboxKind::Flatpak => flatpak_roots
+                .iter()
+                .any(|root| root.join("app").join(self.app_id).is_dir()),
+            SandboxKind::Snap => snap_root.join(self.app_id).join("current").exists(),
+        }
+    }
+}
+
+/// Known sandboxed browsers installed under the given Flatpak installations and snap root.
+pub fn installed_in(flatpak_roots: &[PathBuf], snap_root: &Path) -> Vec<SandboxedBrowser> {
+    KNOWN_SANDBOXED_BROWSERS
+        .into_iter()
+        .filter(|browser| browser.is_installed_in(flatpak_roots, snap_root))
+        .collect()
+}
+
+/// Flatpak installations of the current user and the system.
+pub fn flatpak_roots() -> Vec<PathBuf> {
+    dirs::data_dir()
+        .map(|data| data.join("flatpak"))
+        .into_iter()
+        .chain([PathBuf::from("/var/lib/flatpak")])
+        .collect()
+}
+
+/// Known sandboxed browsers installed for the current user or system-wide.
+pub fn installed() -> Vec<SandboxedBrowser> {
+    installed_in(&flatpak_roots(), Path::new(SNAP_ROOT))
+}
+
+/// The sandboxed browser shown under `name` among already detected browsers.
+///
+/// Only the detected list is consulted, so looking a browser up never rescans the sandboxes.
+pub fn find_listed(browsers: &[BrowserDescriptor], name: &str) -> Option<SandboxedBrowser> {
+    let listed = browsers
+        .iter()
+        .find(|browser| browser.display_name.eq_ignore_ascii_case(name.trim()))?;
+    KNOWN_SANDBOXED_BROWSERS
+        .into_iter()
+        .find(|browser| browser.id() == listed.id)
+}
+
+/// Whether an `Exec` command starts a Flatpak or Snap package, which are listed separately.
+pub fn is_sandboxed_exec(exec: &[String]) -> bool {
+    exec.iter().any(|arg| {
+        arg.rsplit('/').next() == Some("flatpak") || arg.starts_with(&format!("{SNAP_BIN}/"))
+    })
+}
+
+/// The first `<release version="…">` in AppStream metadata, which lists the newest first.
+fn release_version(metainfo: &str) -> Option<String> {
+    let release = &metainfo[metainfo.find("<release ")?..];
+    let release = &release[..release.find('>')?];
+    let start = release.find("version=\"")? + "version=\"".len();
+    let end = release[start..].find('"')? + start;
+    Some(release[start..end].to_string()).filter(|version| !version.is_empty())
+}
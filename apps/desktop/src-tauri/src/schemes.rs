@@ -0,0 +1,75 @@
+use crate::preferences::FallbackPreference;
+use serde::{Deserialize, Serialize};
+
+/// Schemes that are always handled and routed through the fallback browser.
+pub const WEB_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// Schemes that run code or carry inline content, which are never claimed.
+const BLOCKED_SCHEMES: [&str; 5] = ["javascript", "vbscript", "data", "blob", "about"];
+
+/// A scheme beyond http(s) that links are accepted for, such as `mailto` or `ftp`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemeHandler {
+    pub scheme: String,
+    /// Browser that opens links with this scheme when no rule matches; `None` shows the picker.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<FallbackPreference>,
+}
+
+/// Lowercases a scheme and strips a trailing `:` or `://`, rejecting web and unsafe schemes.
+pub fn normalize_scheme(input: &str) -> Result<String, String> {
+    let scheme = input
+        .trim()
+        .trim_end_matches("//")
+        .trim_end_matches(':')
+        .to_ascii_lowercase();
+
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid {
+        return Err(format!("'{}' is not a valid URL scheme.", input.trim()));
+    }
+    if WEB_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!("{scheme} links are always handled."));
+    }
+    if BLOCKED_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!("{scheme} links cannot be handled."));
+    }
+    Ok(scheme)
+}
+
+/// Validates every handler and keeps the first one configured for each scheme.
+pub fn normalize_handlers(handlers: Vec<SchemeHandler>) -> Result<Vec<SchemeHandler>, String> {
+    let mut normalized: Vec<SchemeHandler> = Vec::new();
+    for handler in handlers {
+        let scheme = normalize_scheme(&handler.scheme)?;
+        if normalized.iter().any(|existing| existing.scheme == scheme) {
+            continue;
+        }
+        let target = handler
+            .target
+            .filter(|target| !target.browser.trim().is_empty());
+        normalized.push(SchemeHandler { scheme, target });
+    }
+    Ok(normalized)
+}
+
+/// Every scheme links are accepted for: http(s) followed by the configured ones.
+pub fn handled_schemes(handlers: &[SchemeHandler]) -> Vec<String> {
+    WEB_SCHEMES
+        .iter()
+        .map(|scheme| scheme.to_string())
+        .chain(handlers.iter().map(|handler| handler.scheme.clone()))
+        .collect()
+}
+
+pub fn is_web_scheme(scheme: &str) -> bool {
+    WEB_SCHEMES.contains(&scheme)
+}
+
+/// Whether browsers render links with this scheme themselves rather than handing them off.
+pub fn opens_in_browser(scheme: &str) -> bool {
+    is_web_scheme(scheme) || scheme == "file"
+}
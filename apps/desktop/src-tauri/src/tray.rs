@@ -0,0 +1,65 @@
+use crate::preferences::Preferences;
+use crate::workspaces::activate_workspace;
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::{async_runtime, AppHandle, Manager, Runtime};
+
+pub const TRAY_ID: &str = "main";
+
+/// Menu item ids for workspaces are this prefix followed by the name; empty means none.
+const WORKSPACE_ITEM_PREFIX: &str = "workspace:";
+
+/// Builds the tray menu, including a submenu to switch the active workspace.
+pub fn build_menu<R: Runtime, M: Manager<R>>(
+    manager: &M,
+    preferences: &Preferences,
+) -> tauri::Result<Menu<R>> {
+    let show_item = MenuItemBuilder::with_id("show", "Show window").build(manager)?;
+    let hide_item = MenuItemBuilder::with_id("hide", "Hide window").build(manager)?;
+    let quit_item = MenuItemBuilder::with_id("quit", "Quit").build(manager)?;
+
+    let shared_item = CheckMenuItemBuilder::with_id(WORKSPACE_ITEM_PREFIX, "Shared rules only")
+        .checked(preferences.active_workspace.is_none())
+        .build(manager)?;
+    let mut workspaces = SubmenuBuilder::new(manager, "Workspace").item(&shared_item);
+    if !preferences.workspaces.is_empty() {
+        workspaces = workspaces.separator();
+    }
+    for name in &preferences.workspaces {
+        let item = CheckMenuItemBuilder::with_id(format!("{WORKSPACE_ITEM_PREFIX}{name}"), name)
+            .checked(preferences.active_workspace.as_deref() == Some(name.as_str()))
+            .build(manager)?;
+        workspaces = workspaces.item(&item);
+    }
+    let workspaces = workspaces.build()?;
+
+    MenuBuilder::new(manager)
+        .item(&show_item)
+        .item(&hide_item)
+        .separator()
+        .item(&workspaces)
+        .separator()
+        .item(&quit_item)
+        .build()
+}
+
+/// Rebuilds the tray menu so the workspace submenu reflects `preferences`.
+pub fn refresh_menu(app: &AppHandle, preferences: &Preferences) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app, preferences)?))?;
+    }
+    Ok(())
+}
+
+/// Switches workspace when `id` belongs to an item of the workspace submenu.
+pub fn handle_workspace_item(app: &AppHandle, id: &str) {
+    let Some(name) = id.strip_prefix(WORKSPACE_ITEM_PREFIX) else {
+        return;
+    };
+    let workspace = (!name.is_empty()).then(|| name.to_string());
+    let app = app.clone();
+    async_runtime::spawn(async move {
+        if let Err(err) = activate_workspace(&app, workspace).await {
+            eprintln!("failed to switch workspace: {err}");
+        }
+    });
+}
@@ -0,0 +1,68 @@
+use crate::preferences::{Preferences, PreferencesState};
+use crate::rules::RulesState;
+use crate::tray;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Payload of the `workspace://changed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceChange {
+    pub workspaces: Vec<String>,
+    pub active_workspace: Option<String>,
+}
+
+/// Makes `workspace` the active rule set, or none to apply only shared rules.
+pub async fn activate_workspace(
+    app: &AppHandle,
+    workspace: Option<String>,
+) -> Result<Option<String>, String> {
+    let state = preferences_state(app)?;
+    let active = state.set_active_workspace(app, workspace).await?;
+    apply(app, &state.snapshot().await).await;
+    Ok(active)
+}
+
+/// Replaces the named workspaces; removing the active one leaves only shared rules active.
+pub async fn define_workspaces(
+    app: &AppHandle,
+    workspaces: Vec<String>,
+) -> Result<Preferences, String> {
+    let preferences = preferences_state(app)?
+        .set_workspaces(app, workspaces)
+        .await?;
+    apply(app, &preferences).await;
+    Ok(preferences)
+}
+
+/// Re-applies the stored workspace selection, e.g. after preferences were imported.
+pub async fn sync_workspace(app: &AppHandle) -> Result<(), String> {
+    let preferences = preferences_state(app)?.snapshot().await;
+    apply(app, &preferences).await;
+    Ok(())
+}
+
+/// Points routing, the tray menu and the frontend at the current workspace selection.
+async fn apply(app: &AppHandle, preferences: &Preferences) {
+    if let Some(rules) = app.try_state::<RulesState>() {
+        rules
+            .set_workspace(preferences.active_workspace.clone())
+            .await;
+    }
+
+    if let Err(err) = tray::refresh_menu(app, preferences) {
+        eprintln!("failed to refresh tray menu: {err}");
+    }
+
+    let _ = app.emit(
+        "workspace://changed",
+        WorkspaceChange {
+            workspaces: preferences.workspaces.clone(),
+            active_workspace: preferences.active_workspace.clone(),
+        },
+    );
+}
+
+fn preferences_state(app: &AppHandle) -> Result<tauri::State<'_, PreferencesState>, String> {
+    app.try_state::<PreferencesState>()
+        .ok_or_else(|| "Preferences state not initialised".to_string())
+}
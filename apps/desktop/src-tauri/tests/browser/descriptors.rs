@@ -0,0 +1,189 @@
+use desktop_lib::browser_descriptor::{
+    canonical_catalog_id, detect_version, find_descriptor, find_icon, plist_string, stable_id,
+    BrowserDescriptor, BrowserFamily, ReleaseChannel,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn descriptor(id: &str, display_name: &str) -> BrowserDescriptor {
+    BrowserDescriptor {
+        id: id.to_string(),
+        display_name: display_name.to_string(),
+        family: BrowserFamily::from_name(display_name),
+        executable: None,
+        version: None,
+        channel: ReleaseChannel::from_name(display_name),
+        icon: None,
+    }
+}
+
+fn touch(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().expect("parent")).expect("create parent");
+    fs::write(path, contents).expect("write file");
+}
+
+#[test]
+fn engine_family_comes_from_browser_or_executable_names() {
+    assert_eq!(
+        BrowserFamily::from_name("Google Chrome"),
+        BrowserFamily::Chromium
+    );
+    assert_eq!(
+        BrowserFamily::from_name("/usr/bin/microsoft-edge-dev"),
+        BrowserFamily::Chromium
+    );
+    assert_eq!(BrowserFamily::from_name("LibreWolf"), BrowserFamily::Gecko);
+    assert_eq!(
+        BrowserFamily::from_name("Firefox Developer Edition"),
+        BrowserFamily::Gecko
+    );
+    assert_eq!(BrowserFamily::from_name("Safari"), BrowserFamily::Webkit);
+    assert_eq!(BrowserFamily::from_name("GNOME Web"), BrowserFamily::Webkit);
+    assert_eq!(
+        BrowserFamily::from_name("Search Tool"),
+        BrowserFamily::Unknown
+    );
+}
+
+#[test]
+fn release_channel_comes_from_the_name() {
+    assert_eq!(ReleaseChannel::from_name("Chrome"), ReleaseChannel::Stable);
+    assert_eq!(
+        ReleaseChannel::from_name("Chrome Beta"),
+        ReleaseChannel::Beta
+    );
+    assert_eq!(
+        ReleaseChannel::from_name("google-chrome-unstable"),
+        ReleaseChannel::Dev
+    );
+    assert_eq!(
+        ReleaseChannel::from_name("Firefox Developer Edition"),
+        ReleaseChannel::Dev
+    );
+    assert_eq!(
+        ReleaseChannel::from_name("Chrome SxS"),
+        ReleaseChannel::Canary
+    );
+    assert_eq!(
+        ReleaseChannel::from_name("Firefox Nightly"),
+        ReleaseChannel::Nightly
+    );
+    assert_eq!(
+        ReleaseChannel::from_name("Firefox ESR"),
+        ReleaseChannel::Esr
+    );
+}
+
+#[test]
+fn stable_ids_never_contain_the_profile_separator() {
+    assert_eq!(
+        stable_id(&["flatpak", "org.mozilla.firefox"]),
+        "flatpak-org-mozilla-firefox"
+    );
+    assert_eq!(
+        stable_id(&["desktop", "brave_browser"]),
+        "desktop-brave-browser"
+    );
+}
+
+#[test]
+fn ids_resolve_by_stable_id_before_legacy_name_slugs() {
+    let descriptors = vec![
+        descriptor("desktop-firefox", "Firefox Web Browser"),
+        descriptor("flatpak-org-mozilla-firefox", "Firefox (Flatpak)"),
+        descriptor("firefox-web-browser", "Other Browser"),
+    ];
+
+    let found = find_descriptor(&descriptors, "firefox-flatpak").expect("legacy slug");
+    assert_eq!(found.id, "flatpak-org-mozilla-firefox");
+
+    let found = find_descriptor(&descriptors, "firefox-web-browser").expect("stable id");
+    assert_eq!(found.display_name, "Other Browser");
+
+    assert!(find_descriptor(&descriptors, "safari").is_none());
+}
+
+#[test]
+fn catalog_ids_are_rewritten_to_stable_ids() {
+    let descriptors = vec![descriptor("desktop-firefox", "Firefox Web Browser")];
+
+    assert_eq!(
+        canonical_catalog_id(&descriptors, "firefox-web-browser__abcd-default"),
+        Some("desktop-firefox__abcd-default".to_string())
+    );
+    assert_eq!(
+        canonical_catalog_id(&descriptors, "firefox-web-browser"),
+        Some("desktop-firefox__default".to_string())
+    );
+    assert_eq!(canonical_catalog_id(&descriptors, "chrome__default"), None);
+}
+
+#[test]
+fn reads_versions_from_files_installed_with_the_browser() {
+    let temp = TempDir::new().expect("temp dir");
+
+    let firefox = temp.path().join("firefox/firefox");
+    touch(&firefox, "");
+    touch(
+        &temp.path().join("firefox/application.ini"),
+        "[App]\nVendor=Mozilla\nName=Firefox\nVersion=128.0.3\n\n[Gecko]\nMinVersion=128.0.3\n",
+    );
+    assert_eq!(detect_version(&firefox).as_deref(), Some("128.0.3"));
+
+    let chrome = temp.path().join("Chrome/Application/chrome.exe");
+    touch(&chrome, "");
+    fs::create_dir_all(temp.path().join("Chrome/Application/126.0.6478.127")).expect("dir");
+    fs::create_dir_all(temp.path().join("Chrome/Application/127.0.6533.72")).expect("dir");
+    fs::create_dir_all(temp.path().join("Chrome/Application/SetupMetrics")).expect("dir");
+    assert_eq!(detect_version(&chrome).as_deref(), Some("127.0.6533.72"));
+
+    let safari = temp.path().join("Safari.app/Contents/MacOS/Safari");
+    touch(&safari, "");
+    touch(
+        &temp.path().join("Safari.app/Contents/Info.plist"),
+        "<plist><dict><key>CFBundleShortVersionString</key>\n<string>17.5</string></dict></plist>",
+    );
+    assert_eq!(detect_version(&safari).as_deref(), Some("17.5"));
+
+    let unknown = temp.path().join("bin/browser");
+    touch(&unknown, "");
+    assert_eq!(detect_version(&unknown), None);
+}
+
+#[test]
+fn reads_strings_from_property_lists() {
+    let plist = "<dict><key>CFBundleIconFile</key><string>app.icns</string>\
+                 <key>Empty</key><string> </string></dict>";
+    assert_eq!(
+        plist_string(plist, "CFBundleIconFile").as_deref(),
+        Some("app.icns")
+    );
+    assert_eq!(plist_string(plist, "Empty"), None);
+    assert_eq!(plist_string(plist, "Missing"), None);
+}
+
+#[test]
+fn finds_the_largest_themed_icon_or_a_pixmap() {
+    let temp = TempDir::new().expect("temp dir");
+    let user = temp.path().join("user");
+    let system = temp.path().join("system");
+    touch(&system.join("icons/hicolor/48x48/apps/firefox.png"), "");
+    touch(&system.join("icons/hicolor/256x256/apps/firefox.png"), "");
+    touch(&system.join("pixmaps/brave.xpm"), "");
+    let dirs = vec![user, system.clone()];
+
+    assert_eq!(
+        find_icon("firefox", &dirs),
+        Some(system.join("icons/hicolor/256x256/apps/firefox.png"))
+    );
+    assert_eq!(
+        find_icon("brave", &dirs),
+        Some(system.join("pixmaps/brave.xpm"))
+    );
+    assert_eq!(find_icon("chromium", &dirs), None);
+
+    let absolute = system.join("pixmaps/brave.xpm");
+    assert_eq!(find_icon(&absolute.to_string_lossy(), &[]), Some(absolute));
+    assert!(find_icon("/missing/icon.png", &dirs).is_none());
+}
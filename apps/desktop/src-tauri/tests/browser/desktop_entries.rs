@@ -0,0 +1,109 @@
+use desktop_lib::desktop_entries::{discover_in, parse_desktop_entry, split_exec, ExecTemplate};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const FIREFOX: &str = "[Desktop Entry]
+Type=Application
+Name=Firefox Web Browser
+Name[de]=Firefox-Webbrowser
+Exec=firefox --name \"Firefox %c\" %u
+Icon=firefox
+MimeType=text/html;x-scheme-handler/http;x-scheme-handler/https;
+
+[Desktop Action new-window]
+Exec=firefox --new-window %u
+";
+
+fn write_entry(data_dir: &Path, id: &str, contents: &str) -> PathBuf {
+    let path = data_dir.join("applications").join(id);
+    fs::create_dir_all(path.parent().expect("parent")).expect("create applications dir");
+    fs::write(&path, contents).expect("write desktop entry");
+    path
+}
+
+fn entry(name: &str, exec: &str, extra: &str) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nExec={exec}\n{extra}\
+         MimeType=x-scheme-handler/http;\n"
+    )
+}
+
+#[test]
+fn parses_browser_entries_and_their_launch_command() {
+    let browser = parse_desktop_entry("firefox.desktop", FIREFOX).expect("browser entry");
+    assert_eq!(browser.name, "Firefox Web Browser");
+    assert_eq!(browser.icon.as_deref(), Some("firefox"));
+    assert_eq!(
+        browser.exec_template(),
+        Some(ExecTemplate {
+            program: "firefox".to_string(),
+            leading_args: vec![
+                "--name".to_string(),
+                "Firefox Firefox Web Browser".to_string()
+            ],
+            trailing_args: Vec::new(),
+        })
+    );
+
+    let flatpak = split_exec("flatpak run --file-forwarding org.chromium.Chromium @@u %U @@");
+    assert_eq!(flatpak.last().map(String::as_str), Some("@@"));
+
+    let editor = FIREFOX.replace("x-scheme-handler/http;", "");
+    assert_eq!(parse_desktop_entry("editor.desktop", &editor), None);
+    assert_eq!(
+        parse_desktop_entry("open-with-browser.desktop", FIREFOX),
+        None
+    );
+}
+
+#[test]
+fn discovery_follows_xdg_precedence_and_browser_variable() {
+    let home = TempDir::new().expect("temp dir");
+    let system = TempDir::new().expect("temp dir");
+
+    write_entry(
+        home.path(),
+        "firefox.desktop",
+        &entry("Firefox (local)", "firefox %u", ""),
+    );
+    write_entry(system.path(), "firefox.desktop", FIREFOX);
+    write_entry(
+        system.path(),
+        "vendor/shell.desktop",
+        &entry("Shell", "/bin/sh %u", ""),
+    );
+    write_entry(
+        system.path(),
+        "missing.desktop",
+        &entry("Missing", "missing %u", "TryExec=/nonexistent/browser\n"),
+    );
+    write_entry(
+        system.path(),
+        "open-with-browser.desktop",
+        &entry("Us", "us %u", ""),
+    );
+
+    let dirs = vec![home.path().to_path_buf(), system.path().to_path_buf()];
+    let names = |env: Option<&str>| {
+        discover_in(&dirs, env)
+            .into_iter()
+            .map(|browser| (browser.id, browser.name))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        names(None),
+        vec![
+            ("firefox.desktop".to_string(), "Firefox (local)".to_string()),
+            ("vendor-shell.desktop".to_string(), "Shell".to_string()),
+        ]
+    );
+    assert_eq!(
+        names(Some("sh")),
+        vec![
+            ("vendor-shell.desktop".to_string(), "Shell".to_string()),
+            ("firefox.desktop".to_string(), "Firefox (local)".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,114 @@
+use desktop_lib::browser_descriptor::{BrowserDescriptor, BrowserFamily, ReleaseChannel};
+use desktop_lib::sandbox::{
+    find_listed, installed_in, is_sandboxed_exec, SandboxKind, KNOWN_SANDBOXED_BROWSERS,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn known(kind: SandboxKind, app_id: &str) -> desktop_lib::sandbox::SandboxedBrowser {
+    KNOWN_SANDBOXED_BROWSERS
+        .into_iter()
+        .find(|browser| browser.kind == kind && browser.app_id == app_id)
+        .expect("known sandboxed browser")
+}
+
+#[test]
+fn finds_flatpak_apps_and_snaps_that_are_installed() {
+    let temp = TempDir::new().expect("temp dir");
+    let user_flatpak = temp.path().join("user-flatpak");
+    let system_flatpak = temp.path().join("system-flatpak");
+    let snap_root = temp.path().join("snap");
+    fs::create_dir_all(user_flatpak.join("app/org.mozilla.firefox")).expect("flatpak app");
+    fs::create_dir_all(system_flatpak.join("app/com.brave.Browser")).expect("flatpak app");
+    fs::create_dir_all(snap_root.join("chromium/current")).expect("snap");
+    // A snap without a `current` revision is not installed.
+    fs::create_dir_all(snap_root.join("brave")).expect("snap dir");
+
+    let installed = installed_in(&[user_flatpak, system_flatpak], &snap_root);
+    let names = installed
+        .iter()
+        .map(|browser| browser.display_name())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        names,
+        vec!["Firefox (Flatpak)", "Brave (Flatpak)", "Chromium (Snap)"]
+    );
+}
+
+#[test]
+fn nothing_is_installed_in_empty_roots() {
+    let temp = TempDir::new().expect("temp dir");
+    assert!(installed_in(&[temp.path().to_path_buf()], temp.path()).is_empty());
+}
+
+#[test]
+fn profiles_live_inside_the_sandbox_home() {
+    let home = Path::new("/home/ada");
+
+    assert_eq!(
+        known(SandboxKind::Flatpak, "org.mozilla.firefox").profile_path(home),
+        PathBuf::from("/home/ada/.var/app/org.mozilla.firefox/.mozilla/firefox")
+    );
+    assert_eq!(
+        known(SandboxKind::Flatpak, "com.google.Chrome").profile_path(home),
+        PathBuf::from("/home/ada/.var/app/com.google.Chrome/config/google-chrome/Local State")
+    );
+    assert_eq!(
+        known(SandboxKind::Snap, "firefox").profile_path(home),
+        PathBuf::from("/home/ada/snap/firefox/common/.mozilla/firefox")
+    );
+}
+
+#[test]
+fn snaps_start_through_their_command_wrapper() {
+    let (program, args) = known(SandboxKind::Snap, "chromium").launch_command();
+    assert_eq!(program, PathBuf::from("/snap/bin/chromium"));
+    assert!(args.is_empty());
+
+    let (program, args) = known(SandboxKind::Flatpak, "com.brave.Browser").launch_command();
+    assert!(program.ends_with("flatpak"));
+    assert_eq!(args, vec!["run", "com.brave.Browser"]);
+}
+
+#[test]
+fn recognises_sandboxed_desktop_entry_commands() {
+    let exec = |command: &str| {
+        command
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(is_sandboxed_exec(&exec(
+        "/usr/bin/flatpak run --branch=stable org.mozilla.firefox @@u %u @@"
+    )));
+    assert!(is_sandboxed_exec(&exec(
+        "env BAMF_DESKTOP_FILE_HINT=x /snap/bin/firefox %u"
+    )));
+    assert!(!is_sandboxed_exec(&exec("/usr/bin/firefox %u")));
+    assert!(!is_sandboxed_exec(&exec("/usr/lib/snapshot-viewer %u")));
+}
+
+#[test]
+fn sandboxed_browsers_are_found_among_detected_browsers_only() {
+    let flatpak = known(SandboxKind::Flatpak, "org.mozilla.firefox");
+    let listed = |id: String, display_name: &str| BrowserDescriptor {
+        id,
+        display_name: display_name.to_string(),
+        family: BrowserFamily::Gecko,
+        executable: None,
+        version: None,
+        channel: ReleaseChannel::Stable,
+        icon: None,
+    };
+    let browsers = vec![
+        listed("firefox".to_string(), "Firefox"),
+        listed(flatpak.id(), &flatpak.display_name()),
+    ];
+
+    assert_eq!(find_listed(&browsers, " firefox (flatpak) "), Some(flatpak));
+    assert_eq!(find_listed(&browsers, "Firefox"), None);
+    assert_eq!(find_listed(&browsers, "Chromium (Snap)"), None);
+}
@@ -0,0 +1,63 @@
+use desktop_lib::cleaning::unwrap_redirects;
+
+#[test]
+fn unwraps_known_wrapper_formats() {
+    let cases = [
+        (
+            "https://eur01.safelinks.protection.outlook.com/?url=https%3A%2F%2Fgithub.com%2Facme%3Ftab%3D1&data=05%7C01&reserved=0",
+            "https://github.com/acme?tab=1",
+        ),
+        (
+            "https://www.google.co.uk/url?sa=t&q=https://docs.rs/url&usg=x",
+            "https://docs.rs/url",
+        ),
+        (
+            "https://slack-redir.net/link?url=https%3A%2F%2Fexample.com%2Fa",
+            "https://example.com/a",
+        ),
+        (
+            "https://l.facebook.com/l.php?u=https%3A%2F%2Fnews.example%2Fstory&h=AT0",
+            "https://news.example/story",
+        ),
+        (
+            "https://www.linkedin.com/redir/redirect?url=https%3A%2F%2Fexample.org&urlhash=abc",
+            "https://example.org/",
+        ),
+        (
+            "https://www.youtube.com/redirect?event=video&q=https%3A%2F%2Fexample.net%2F",
+            "https://example.net/",
+        ),
+    ];
+
+    for (wrapped, expected) in cases {
+        assert_eq!(
+            unwrap_redirects(wrapped).as_deref(),
+            Some(expected),
+            "{wrapped}"
+        );
+    }
+}
+
+#[test]
+fn follows_nested_wrappers_and_ignores_everything_else() {
+    let nested = "https://nam02.safelinks.protection.outlook.com/?url=https%3A%2F%2Fwww.google.com%2Furl%3Fq%3Dhttps%253A%252F%252Fexample.com%252Fdeep";
+    assert_eq!(
+        unwrap_redirects(nested).as_deref(),
+        Some("https://example.com/deep")
+    );
+
+    assert_eq!(
+        unwrap_redirects("https://www.google.com/search?q=https://x.test"),
+        None
+    );
+    assert_eq!(
+        unwrap_redirects("https://googleusercontent.com/url?q=https://x.test"),
+        None
+    );
+    assert_eq!(
+        unwrap_redirects("https://l.facebook.com/l.php?u=javascript:alert(1)"),
+        None
+    );
+    assert_eq!(unwrap_redirects("https://slack-redir.net/link"), None);
+    assert_eq!(unwrap_redirects("https://github.com/acme"), None);
+}
@@ -0,0 +1,87 @@
+use desktop_lib::cleaning::{expand_short_link, ShortLinkCache, ShortLinkExpansion};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Serves one canned response per request on a local port, standing in for a shortener.
+fn stand_in(responses: Vec<&'static str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind local server");
+    let address = listener.local_addr().expect("local address");
+    thread::spawn(move || {
+        for response in responses {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    format!("http://{address}")
+}
+
+fn settings(timeout_ms: u64) -> ShortLinkExpansion {
+    ShortLinkExpansion {
+        enabled: true,
+        shorteners: vec!["127.0.0.1".to_string()],
+        timeout_ms,
+    }
+}
+
+#[test]
+fn follows_shortener_redirects_to_the_destination() {
+    let server = stand_in(vec![
+        "HTTP/1.1 301 Moved Permanently\r\nLocation: /hop\r\nContent-Length: 0\r\n\r\n",
+        "HTTP/1.1 302 Found\r\nLocation: https://example.com/article?id=1\r\nContent-Length: 0\r\n\r\n",
+    ]);
+
+    assert_eq!(
+        expand_short_link(&format!("{server}/abc"), &settings(2000)).as_deref(),
+        Some("https://example.com/article?id=1")
+    );
+}
+
+#[test]
+fn fails_open_on_errors_timeouts_and_unknown_hosts() {
+    let server = stand_in(vec!["HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"]);
+    assert_eq!(
+        expand_short_link(&format!("{server}/abc"), &settings(2000)),
+        None
+    );
+
+    let silent = TcpListener::bind("127.0.0.1:0").expect("bind local server");
+    let url = format!("http://{}/abc", silent.local_addr().expect("local address"));
+    let started = Instant::now();
+    assert_eq!(expand_short_link(&url, &settings(200)), None);
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    let disabled = ShortLinkExpansion::default();
+    assert!(!disabled.enabled);
+    assert_eq!(expand_short_link("https://bit.ly/abc", &disabled), None);
+    assert_eq!(
+        expand_short_link("https://example.com/abc", &settings(200)),
+        None
+    );
+}
+
+#[test]
+fn cache_drops_the_oldest_expansions_first() {
+    let mut cache = ShortLinkCache::default();
+    for index in 0..1001 {
+        cache.insert(
+            &format!("https://bit.ly/{index}"),
+            "https://example.com/",
+            &format!("2024-01-01T00:{:02}:{:02}Z", index / 60, index % 60),
+        );
+    }
+    assert_eq!(cache.len(), 1000);
+    assert_eq!(cache.get("https://bit.ly/0"), None);
+    assert_eq!(
+        cache.get("https://bit.ly/1000"),
+        Some("https://example.com/")
+    );
+}
@@ -0,0 +1,56 @@
+use desktop_lib::cleaning::{strip_tracking_parameters, TrackingCleanup};
+
+fn cleaned(url: &str, cleanup: &TrackingCleanup) -> Option<String> {
+    strip_tracking_parameters(url, cleanup).map(|(url, _)| url)
+}
+
+#[test]
+fn strips_default_tracking_parameters_and_keeps_the_rest() {
+    let cleanup = TrackingCleanup::default();
+    let (url, removed) = strip_tracking_parameters(
+        "https://example.com/post?id=7&UTM_Source=mail&utm_campaign=x&q=a%20b&fbclid=abc#top",
+        &cleanup,
+    )
+    .expect("parameters removed");
+
+    assert_eq!(url, "https://example.com/post?id=7&q=a%20b#top");
+    assert_eq!(removed, vec!["UTM_Source", "utm_campaign", "fbclid"]);
+    assert_eq!(
+        cleaned("https://example.com/?gclid=1&mc_eid=2", &cleanup),
+        Some("https://example.com/".to_string())
+    );
+    assert_eq!(cleaned("https://example.com/?id=7", &cleanup), None);
+    assert_eq!(cleaned("https://example.com/", &cleanup), None);
+}
+
+#[test]
+fn exceptions_and_custom_lists_are_respected() {
+    let cleanup = TrackingCleanup {
+        enabled: true,
+        parameters: vec![" REF ".to_string(), "utm_*".to_string()],
+        exceptions: vec![
+            "https://Analytics.Example.com/".to_string(),
+            "*.test".to_string(),
+        ],
+    }
+    .normalized();
+    assert_eq!(cleanup.parameters, vec!["ref", "utm_*"]);
+    assert_eq!(cleanup.exceptions, vec!["analytics.example.com", "*.test"]);
+
+    let url = "https://eu.analytics.example.com/?utm_source=x";
+    assert_eq!(cleaned(url, &cleanup), None);
+    assert_eq!(cleaned("https://shop.test/?ref=a", &cleanup), None);
+    assert_eq!(
+        cleaned("https://example.com/?ref=a&fbclid=b", &cleanup),
+        Some("https://example.com/?fbclid=b".to_string())
+    );
+
+    let disabled = TrackingCleanup {
+        enabled: false,
+        ..TrackingCleanup::default()
+    };
+    assert_eq!(
+        cleaned("https://example.com/?utm_source=x", &disabled),
+        None
+    );
+}
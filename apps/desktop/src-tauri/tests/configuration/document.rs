@@ -0,0 +1,215 @@
+use desktop_lib::configuration::{
+    apply_import, diff_configuration, ConfigDocument, ConfigFormat, ImportMode, CONFIG_VERSION,
+};
+use desktop_lib::preferences::{FallbackPreference, Preferences, ProfilePreference};
+use desktop_lib::rules::{RuleKind, RulesSnapshot};
+
+fn rules(domain: &[(&str, &str)]) -> RulesSnapshot {
+    let rules = domain
+        .iter()
+        .map(|(id, pattern)| serde_json::json!({ "id": id, "pattern": pattern }))
+        .collect::<Vec<_>>();
+    serde_json::from_value(serde_json::json!({
+        "domainRules": rules,
+        "fileTypeRules": [{ "id": "pdf", "extension": ".pdf", "browserLabel": "Prompt me" }],
+    }))
+    .expect("rules snapshot")
+}
+
+fn preferences(browser: Option<&str>) -> Preferences {
+    Preferences {
+        fallback: browser.map(|browser| FallbackPreference {
+            browser: browser.to_string(),
+            browser_id: None,
+            profile: Some(ProfilePreference {
+                label: Some("Work".to_string()),
+                directory: Some("Profile 1".to_string()),
+            }),
+        }),
+        ..Preferences::default()
+    }
+}
+
+#[test]
+fn documents_round_trip_through_json_and_toml() {
+    let document = ConfigDocument::new(
+        preferences(Some("Google Chrome")),
+        rules(&[("github", "github.com"), ("figma", "*.figma.com")]),
+        "2026-10-17T09:00:00Z".to_string(),
+    );
+
+    for format in [ConfigFormat::Json, ConfigFormat::Toml] {
+        let contents = document.to_string(format).expect("serialize");
+        let parsed = ConfigDocument::parse(&contents, None).expect("parse");
+        assert_eq!(parsed.version, CONFIG_VERSION);
+        assert_eq!(parsed.rules.domain_rules.len(), 2);
+        assert_eq!(parsed.rules.domain_rules[1].pattern, "*.figma.com");
+        assert_eq!(parsed.rules.file_type_rules[0].extension, ".pdf");
+        let fallback = parsed.preferences.fallback.expect("fallback");
+        assert_eq!(fallback.browser, "Google Chrome");
+        assert_eq!(
+            fallback.profile.and_then(|p| p.directory).as_deref(),
+            Some("Profile 1")
+        );
+    }
+}
+
+#[test]
+fn parse_rejects_unknown_versions_and_invalid_rules() {
+    let err = ConfigDocument::parse(r#"{ "version": 99 }"#, None).unwrap_err();
+    assert!(err.contains("version 99"));
+
+    let document = ConfigDocument::parse(
+        r#"{ "version": 1, "rules": { "domainRules": [{ "pattern": "https://bad.example/" }] } }"#,
+        Some(ConfigFormat::Json),
+    )
+    .expect("parse");
+    assert!(document.normalized().is_err());
+
+    let document = ConfigDocument::parse(
+        "version = 1\n[[rules.domainRules]]\npattern = \"*.slack.com\"\n",
+        None,
+    )
+    .expect("parse toml")
+    .normalized()
+    .expect("valid rules");
+    assert!(!document.rules.domain_rules[0].id.is_empty());
+}
+
+#[test]
+fn merge_keeps_local_rules_and_replace_discards_them() {
+    let current_rules = rules(&[("github", "github.com"), ("local", "intranet.local")]);
+    let current_preferences = preferences(Some("Firefox"));
+    let imported = ConfigDocument::new(
+        preferences(None),
+        rules(&[("github", "*.github.com"), ("jira", "*.atlassian.net")]),
+        String::new(),
+    );
+
+    let (merged_preferences, merged) = apply_import(
+        &current_preferences,
+        &current_rules,
+        imported.clone(),
+        ImportMode::Merge,
+    );
+    let ids = merged
+        .domain_rules
+        .iter()
+        .map(|rule| rule.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["github", "local", "jira"]);
+    assert_eq!(merged.domain_rules[0].pattern, "*.github.com");
+    assert_eq!(
+        merged_preferences
+            .fallback
+            .as_ref()
+            .map(|f| f.browser.as_str()),
+        Some("Firefox")
+    );
+
+    let diff = diff_configuration(
+        &current_preferences,
+        &current_rules,
+        &merged_preferences,
+        &merged,
+    );
+    assert_eq!(
+        diff.added.iter().map(|c| c.id.as_str()).collect::<Vec<_>>(),
+        vec!["jira"]
+    );
+    assert_eq!(diff.updated[0].id, "github");
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.unchanged, 2);
+    assert!(diff.fallback.is_none());
+
+    let (replaced_preferences, replaced) = apply_import(
+        &current_preferences,
+        &current_rules,
+        imported,
+        ImportMode::Replace,
+    );
+    let diff = diff_configuration(
+        &current_preferences,
+        &current_rules,
+        &replaced_preferences,
+        &replaced,
+    );
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].id, "local");
+    assert_eq!(diff.removed[0].kind, RuleKind::Domain);
+    assert!(diff.fallback.is_some());
+    assert!(!diff.is_empty());
+}
+
+#[test]
+fn merge_combines_workspaces_and_keeps_the_local_active_one() {
+    let mut local = preferences(None);
+    local.workspaces = vec!["Office".to_string(), "Weekend".to_string()];
+    local.active_workspace = Some("Office".to_string());
+
+    let mut imported = preferences(None);
+    imported.workspaces = vec![" weekend ".to_string(), "On call".to_string()];
+    imported.active_workspace = Some("On call".to_string());
+    let document = ConfigDocument::new(imported, RulesSnapshot::default(), String::new());
+
+    let (merged, _) = apply_import(
+        &local,
+        &RulesSnapshot::default(),
+        document,
+        ImportMode::Merge,
+    );
+    assert_eq!(merged.workspaces, vec!["Office", "Weekend", "On call"]);
+    assert_eq!(merged.active_workspace.as_deref(), Some("Office"));
+}
+
+#[test]
+fn merge_combines_schemes_and_takes_settings_the_document_changed() {
+    let mut local = preferences(None);
+    local.tracking.exceptions = vec!["intranet.local".to_string()];
+    local.schemes = serde_json::from_value(serde_json::json!([
+        { "scheme": "mailto" },
+        { "scheme": "ftp", "target": { "browser": "Firefox" } },
+    ]))
+    .expect("local schemes");
+
+    let mut imported = preferences(None);
+    imported.short_links.enabled = true;
+    imported.schemes = serde_json::from_value(serde_json::json!([
+        { "scheme": "ftp" },
+        { "scheme": "zoommtg" },
+    ]))
+    .expect("imported schemes");
+    let document = ConfigDocument::new(imported, RulesSnapshot::default(), String::new());
+
+    let (merged, _) = apply_import(
+        &local,
+        &RulesSnapshot::default(),
+        document,
+        ImportMode::Merge,
+    );
+    let schemes = merged
+        .schemes
+        .iter()
+        .map(|handler| (handler.scheme.as_str(), handler.target.is_some()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        schemes,
+        vec![("mailto", false), ("ftp", false), ("zoommtg", false)]
+    );
+    assert_eq!(merged.tracking.exceptions, vec!["intranet.local"]);
+    assert!(merged.short_links.enabled);
+
+    let diff = diff_configuration(
+        &local,
+        &RulesSnapshot::default(),
+        &merged,
+        &RulesSnapshot::default(),
+    );
+    let names = diff
+        .preferences
+        .iter()
+        .map(|change| change.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["shortLinks", "schemes"]);
+    assert!(!diff.is_empty());
+}
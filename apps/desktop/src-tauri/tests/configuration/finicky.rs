@@ -0,0 +1,174 @@
+use desktop_lib::configuration::{translate_finicky_config, FinickyBrowser, ResolvedBrowser};
+use desktop_lib::rules::DomainMatchType;
+
+const CONFIG: &str = r#"
+// ~/.finicky.js
+module.exports = {
+  defaultBrowser: "Safari",
+  options: { hideIcon: false },
+  rewrite: [{ match: () => true, url: ({ url }) => url }],
+  handlers: [
+    {
+      // Work tools always open in the work profile.
+      match: ["*.atlassian.net/*", "https://github.com/acme/*", /zoom\.us\/j\//],
+      browser: { name: "Google Chrome", profile: "Profile 1" },
+    },
+    {
+      match: finicky.matchHostnames(["figma.com", /^.*\.notion\.so$/]),
+      browser: "com.google.Chrome",
+    },
+    {
+      match: "https://example.com/",
+      browser: ["Firefox", "Safari"],
+    },
+    {
+      match: ({ url }) => url.host.endsWith(".local"),
+      browser: "Firefox",
+    },
+    {
+      match: "*.slack.com/*",
+      browser: (url) => (url.pathname.length > 3 ? "Slack" : "Safari"),
+    },
+    {
+      match: ["mailto:*", `${base}/docs`, "*.atlassian.net/*"],
+      browser: "Firefox Developer Edition",
+    },
+    {
+      match(url) { return url.protocol === "ftp" },
+      browser: "Firefox",
+    },
+  ],
+};
+"#;
+
+fn resolve(browser: &FinickyBrowser) -> ResolvedBrowser {
+    ResolvedBrowser {
+        id: format!(
+            "{}__{}",
+            browser.name.to_lowercase().replace(' ', "-"),
+            browser.profile.as_deref().unwrap_or("default")
+        ),
+        name: browser.name.clone(),
+        profile_label: None,
+        profile_directory: browser.profile.clone(),
+    }
+}
+
+#[test]
+fn literal_handlers_become_domain_rules() {
+    let import = translate_finicky_config(CONFIG, resolve).expect("config parses");
+    let rules = import
+        .document
+        .rules
+        .domain_rules
+        .iter()
+        .map(|rule| {
+            (
+                rule.match_type.expect("match type"),
+                rule.pattern.as_str(),
+                rule.browser_id.as_deref().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        rules[..4],
+        [
+            (
+                DomainMatchType::Wildcard,
+                "*.atlassian.net/*",
+                "google-chrome__Profile 1"
+            ),
+            (
+                DomainMatchType::Wildcard,
+                "github.com/acme/*",
+                "google-chrome__Profile 1"
+            ),
+            (
+                DomainMatchType::Regex,
+                r"zoom\.us/j/",
+                "google-chrome__Profile 1"
+            ),
+            (DomainMatchType::Host, "figma.com", "google-chrome__default"),
+        ]
+    );
+    assert_eq!(rules[4].0, DomainMatchType::Regex);
+    assert_eq!(
+        rules[5],
+        (DomainMatchType::Host, "example.com", "firefox__default")
+    );
+    assert_eq!(rules.len(), 6);
+    assert_eq!(
+        import.document.rules.domain_rules[0].browser_label,
+        "Google Chrome · Profile 1"
+    );
+
+    let fallback = import.document.preferences.fallback.expect("fallback");
+    assert_eq!(fallback.browser, "Safari");
+}
+
+#[test]
+fn hostname_regexes_are_pinned_to_the_host() {
+    let import = translate_finicky_config(CONFIG, resolve).expect("config parses");
+    let pattern = regex::Regex::new(&import.document.rules.domain_rules[4].pattern).expect("regex");
+    assert!(pattern.is_match("https://acme.notion.so/page"));
+    assert!(!pattern.is_match("https://notion.so.evil.example/"));
+    assert!(!pattern.is_match("https://example.com/?next=acme.notion.so"));
+}
+
+#[test]
+fn runtime_handlers_are_reported_not_guessed() {
+    let import = translate_finicky_config(CONFIG, resolve).expect("config parses");
+    let reported = import
+        .untranslated
+        .iter()
+        .map(|entry| (entry.handler, entry.reason.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        reported[0],
+        (
+            4,
+            "Matcher is a function and cannot be translated statically."
+        )
+    );
+    assert_eq!(
+        reported[1],
+        (
+            5,
+            "Browser is a function and cannot be translated statically."
+        )
+    );
+    assert_eq!(
+        reported[2],
+        (
+            6,
+            "Matcher 'mailto:*' targets the 'mailto' scheme, which rules cannot match."
+        )
+    );
+    assert_eq!(
+        reported[3],
+        (
+            6,
+            "Matcher is a template string and cannot be translated statically."
+        )
+    );
+    assert_eq!(reported[4].0, 6);
+    assert!(reported[4].1.contains("already handled"));
+    assert_eq!(reported[5].0, 7);
+    assert_eq!(reported.len(), 6);
+    assert!(import.untranslated[0]
+        .source
+        .starts_with("{ match: ({ url }) =>"));
+}
+
+#[test]
+fn configs_without_an_export_are_rejected() {
+    assert!(translate_finicky_config("const config = {};", resolve).is_err());
+    let import = translate_finicky_config(
+        "export default { defaultBrowser: 'Firefox', handlers: [] } satisfies FinickyConfig;",
+        resolve,
+    )
+    .expect("v4 config parses");
+    assert!(import.document.rules.domain_rules.is_empty());
+}
@@ -0,0 +1,68 @@
+use desktop_lib::link::{extract_text_urls, extract_urls, parse_link_file, LinkFileKind};
+use std::fs;
+
+const WEB: [&str; 2] = ["http", "https"];
+
+#[test]
+fn text_extraction_drops_surrounding_punctuation_and_markup() {
+    let text = "See <https://example.com/a>. Docs: [guide](https://docs.rs/x_(y)), \
+                or www.rust-lang.org! Ignore ftp://files.test and xhttps://nope.test";
+    assert_eq!(
+        extract_text_urls(text, &WEB),
+        vec![
+            "https://example.com/a",
+            "https://docs.rs/x_(y)",
+            "https://www.rust-lang.org/",
+        ]
+    );
+    assert_eq!(
+        extract_text_urls("mail mailto:me@example.com, thanks", &["mailto"]),
+        vec!["mailto:me@example.com"]
+    );
+}
+
+#[test]
+fn link_files_yield_their_target() {
+    let shortcut = b"[InternetShortcut]\r\nIconIndex=0\r\nURL=https://example.com/page\r\n";
+    assert_eq!(
+        parse_link_file(LinkFileKind::InternetShortcut, shortcut),
+        Some("https://example.com/page".to_string())
+    );
+
+    let webloc = br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0"><dict><key>URL</key><string>https://example.com/?a=1&amp;b=2</string></dict></plist>"#;
+    assert_eq!(
+        parse_link_file(LinkFileKind::Webloc, webloc),
+        Some("https://example.com/?a=1&b=2".to_string())
+    );
+
+    let desktop = b"[Desktop Entry]\nName=Example\nType=Link\nURL[$e]=https://example.com/\n";
+    assert_eq!(
+        parse_link_file(LinkFileKind::DesktopLink, desktop),
+        Some("https://example.com/".to_string())
+    );
+    let application = b"[Desktop Entry]\nType=Application\nURL=https://example.com/\n";
+    assert_eq!(
+        parse_link_file(LinkFileKind::DesktopLink, application),
+        None
+    );
+}
+
+#[test]
+fn arguments_may_be_link_files_or_free_text() {
+    let path = std::env::temp_dir().join(format!("owb-extraction-{}.url", std::process::id()));
+    fs::write(&path, "[InternetShortcut]\nURL=https://shortcut.test/\n").expect("write shortcut");
+
+    let args = vec![
+        path.to_string_lossy().into_owned(),
+        "see https://text.test/page.".to_string(),
+        "--flag".to_string(),
+    ];
+    let urls = extract_urls(&args);
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(
+        urls,
+        vec!["https://shortcut.test/", "https://text.test/page"]
+    );
+}
@@ -0,0 +1,35 @@
+use desktop_lib::homograph::{check_host, LinkWarningKind};
+use url::Url;
+
+fn warning(url: &str) -> Option<(LinkWarningKind, Option<String>)> {
+    let url = Url::parse(url).expect("valid url");
+    check_host(&url).map(|warning| (warning.kind, warning.lookalike))
+}
+
+#[test]
+fn flags_mixed_scripts_and_whole_script_lookalikes() {
+    // Cyrillic "а" in an otherwise Latin label.
+    assert_eq!(
+        warning("https://\u{430}pple.com/login"),
+        Some((LinkWarningKind::MixedScript, Some("apple.com".to_string())))
+    );
+    // Entirely Cyrillic, but reads as "coco".
+    assert_eq!(
+        warning("https://\u{441}\u{43e}\u{441}\u{43e}.com/"),
+        Some((LinkWarningKind::Confusable, Some("coco.com".to_string())))
+    );
+
+    let url = Url::parse("https://xn--pple-43d.com/").expect("valid url");
+    let warning = check_host(&url).expect("warning");
+    assert_eq!(warning.display_host, "\u{430}pple.com");
+    assert_eq!(warning.ascii_host, "xn--pple-43d.com");
+}
+
+#[test]
+fn leaves_ordinary_hosts_alone() {
+    assert_eq!(warning("https://apple.com/"), None);
+    assert_eq!(warning("https://münchen.de/"), None);
+    assert_eq!(warning("https://пример.рф/"), None);
+    assert_eq!(warning("https://東京.jp/"), None);
+    assert_eq!(warning("http://127.0.0.1:8080/"), None);
+}
@@ -0,0 +1,62 @@
+use desktop_lib::link::{extract_urls_in, local_file_url};
+use desktop_lib::rules::LinkFacts;
+use std::fs;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("owb-{name}-{}", std::process::id()));
+    fs::create_dir_all(dir.join("build")).expect("create scratch dir");
+    fs::canonicalize(dir).expect("canonical scratch dir")
+}
+
+#[test]
+fn local_paths_resolve_against_the_callers_directory() {
+    let dir = scratch_dir("local-paths");
+    let page = dir.join("build").join("index.html");
+    fs::write(&page, "<html></html>").expect("write page");
+    let expected = Url::from_file_path(&page).expect("file url").to_string();
+
+    assert_eq!(
+        local_file_url("build/index.html", &dir),
+        Some(expected.clone())
+    );
+    assert_eq!(
+        local_file_url("file:build/index.html", &dir),
+        Some(expected.clone())
+    );
+    assert_eq!(
+        local_file_url(&expected, Path::new("/")),
+        Some(expected.clone())
+    );
+    assert_eq!(local_file_url("build/missing.html", &dir), None);
+    assert_eq!(local_file_url("build", &dir), None);
+
+    let facts = LinkFacts::from_url(&expected).expect("valid url");
+    assert_eq!(facts.extension().as_deref(), Some(".html"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn extract_urls_in_mixes_files_and_links() {
+    let dir = scratch_dir("local-args");
+    let page = dir.join("notes.html");
+    fs::write(&page, "<p>notes</p>").expect("write page");
+
+    let args = vec![
+        "notes.html".to_string(),
+        "https://example.com".to_string(),
+        "--verbose".to_string(),
+    ];
+    let urls = extract_urls_in(&args, &["http", "https"], &dir);
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(
+        urls,
+        vec![
+            Url::from_file_path(&page).expect("file url").to_string(),
+            "https://example.com/".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,51 @@
+use desktop_lib::link::{extract_urls_for, parse_candidate_for};
+use desktop_lib::schemes::{handled_schemes, normalize_handlers, normalize_scheme, SchemeHandler};
+
+fn handler(scheme: &str) -> SchemeHandler {
+    SchemeHandler {
+        scheme: scheme.to_string(),
+        target: None,
+    }
+}
+
+#[test]
+fn normalize_scheme_accepts_custom_schemes_and_rejects_unsafe_ones() {
+    assert_eq!(normalize_scheme(" MailTo: "), Ok("mailto".to_string()));
+    assert_eq!(normalize_scheme("ftp://"), Ok("ftp".to_string()));
+    assert_eq!(normalize_scheme("web+app"), Ok("web+app".to_string()));
+    assert!(normalize_scheme("https").is_err());
+    assert!(normalize_scheme("javascript:").is_err());
+    assert!(normalize_scheme("1password").is_err());
+    assert!(normalize_scheme("").is_err());
+
+    let handlers = normalize_handlers(vec![handler("mailto"), handler("MAILTO:"), handler("ftp")])
+        .expect("valid handlers");
+    assert_eq!(
+        handled_schemes(&handlers),
+        vec!["http", "https", "mailto", "ftp"]
+    );
+}
+
+#[test]
+fn configured_schemes_are_extracted_from_arguments() {
+    let schemes = ["http", "https", "mailto", "file"];
+    assert_eq!(
+        parse_candidate_for("mailto:someone@example.com", &schemes),
+        Some("mailto:someone@example.com".to_string())
+    );
+    assert_eq!(
+        parse_candidate_for("FILE:///tmp/page.html", &schemes),
+        Some("file:///tmp/page.html".to_string())
+    );
+    assert_eq!(parse_candidate_for("ftp://example.com", &schemes), None);
+
+    let args = vec![
+        "--url=mailto:a@example.com".to_string(),
+        "ftp://example.com".to_string(),
+        "https://example.com".to_string(),
+    ];
+    assert_eq!(
+        extract_urls_for(&args, &schemes),
+        vec!["mailto:a@example.com", "https://example.com/"]
+    );
+}
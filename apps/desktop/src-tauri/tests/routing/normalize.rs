@@ -0,0 +1,58 @@
+use desktop_lib::routing::normalize_url;
+
+#[test]
+fn normalize_url_table() {
+    let cases = [
+        // Already complete URLs are kept verbatim.
+        ("https://example.com", "https://example.com"),
+        ("  http://example.com/a?b=1  ", "http://example.com/a?b=1"),
+        ("http://localhost:3000/app", "http://localhost:3000/app"),
+        ("https://[::1]:8443/", "https://[::1]:8443/"),
+        ("mailto:someone@example.com", "mailto:someone@example.com"),
+        ("tel:5551234", "tel:5551234"),
+        ("about:blank", "about:blank"),
+        // Public hosts default to https.
+        ("example.com", "https://example.com"),
+        (
+            "www.example.com/path?q=1#top",
+            "https://www.example.com/path?q=1#top",
+        ),
+        ("example.com:8443/admin", "https://example.com:8443/admin"),
+        ("8.8.8.8", "https://8.8.8.8"),
+        ("//cdn.example.com/lib.js", "https://cdn.example.com/lib.js"),
+        // Dev servers and loopback addresses use http.
+        ("localhost", "http://localhost"),
+        ("localhost:3000", "http://localhost:3000"),
+        (
+            "LOCALHOST:5173/index.html",
+            "http://LOCALHOST:5173/index.html",
+        ),
+        ("app.localhost:8080", "http://app.localhost:8080"),
+        ("127.0.0.1:8000/docs", "http://127.0.0.1:8000/docs"),
+        ("0.0.0.0:4000", "http://0.0.0.0:4000"),
+        ("[::1]:5173", "http://[::1]:5173"),
+        ("::1", "http://[::1]"),
+        // Private networks and intranet names use http.
+        ("192.168.1.10:8080", "http://192.168.1.10:8080"),
+        ("10.0.0.5", "http://10.0.0.5"),
+        ("172.16.4.2/status", "http://172.16.4.2/status"),
+        ("169.254.10.1", "http://169.254.10.1"),
+        ("[fd12:3456::1]:9000", "http://[fd12:3456::1]:9000"),
+        ("[fe80::1]", "http://[fe80::1]"),
+        ("jenkins", "http://jenkins"),
+        ("wiki:8090/display/ENG", "http://wiki:8090/display/ENG"),
+        ("printer.local", "http://printer.local"),
+        ("nas.home.arpa:5000", "http://nas.home.arpa:5000"),
+        ("api.internal/health", "http://api.internal/health"),
+        // Public addresses in private-looking ranges stay https.
+        ("172.32.0.1", "https://172.32.0.1"),
+        // Input that cannot become a URL is returned trimmed.
+        ("", ""),
+        ("   ", ""),
+        ("not a url", "not a url"),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(normalize_url(input), expected, "input: {input:?}");
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::NaiveDateTime;
+use desktop_lib::domain::models::{Action, Condition, Rule, RulePolicy};
+use desktop_lib::rules::{explain_rules, LinkFacts, RuleStatus};
+
+fn condition(fact: &str, operator: &str, value: &str) -> Condition {
+    Condition {
+        fact: fact.to_string(),
+        operator: operator.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn rule(id: &str, priority: i32, conditions: Vec<Condition>) -> Rule {
+    Rule {
+        id: id.to_string(),
+        rule_name: id.to_string(),
+        priority,
+        conditions,
+        action: Action {
+            profile: String::new(),
+            browser: String::new(),
+            url: String::new(),
+            policy: RulePolicy::Always,
+        },
+        enabled: true,
+    }
+}
+
+fn facts(url: &str) -> LinkFacts {
+    LinkFacts::from_url(url)
+        .expect("valid url")
+        .with_source("Slack", None)
+        .with_time(
+            NaiveDateTime::parse_from_str("2026-10-16 10:30", "%Y-%m-%d %H:%M")
+                .expect("valid timestamp"),
+        )
+}
+
+#[test]
+fn every_rule_is_reported_in_priority_order() {
+    let mut disabled = rule("disabled", 0, vec![condition("url", "host", "github.com")]);
+    disabled.enabled = false;
+    let rules = vec![
+        rule("catch-all", 4, vec![condition("url", "wildcard", "*.com")]),
+        rule("gitlab", 1, vec![condition("url", "host", "gitlab.com")]),
+        disabled,
+        rule("broken", 2, vec![condition("url", "regex", "(")]),
+        rule(
+            "github",
+            3,
+            vec![
+                condition("url", "host", "github.com"),
+                condition("source_app", "contains", "slack"),
+            ],
+        ),
+    ];
+
+    let report = explain_rules(&rules, &facts("https://github.com/org/repo"));
+    let statuses = report
+        .iter()
+        .map(|entry| (entry.rule_id.as_str(), entry.status))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            ("disabled", RuleStatus::Disabled),
+            ("gitlab", RuleStatus::NotMatched),
+            ("broken", RuleStatus::Invalid),
+            ("github", RuleStatus::Selected),
+            ("catch-all", RuleStatus::Shadowed),
+        ]
+    );
+
+    assert_eq!(
+        report[1].reason,
+        "url host 'gitlab.com' did not match 'github.com'."
+    );
+    assert!(report[2].reason.contains("Invalid regular expression"));
+    assert_eq!(report[3].conditions[1].subject.as_deref(), Some("Slack"));
+    assert!(report[4].reason.contains("'github' has higher priority"));
+}
+
+#[test]
+fn condition_subjects_show_the_compared_fact() {
+    let rules = vec![rule(
+        "office-hours",
+        0,
+        vec![
+            condition("url", "wildcard", "*.example.com/docs/*"),
+            condition("weekday", "in", "sat,sun"),
+            condition("time", "between", "09:00-17:00"),
+            condition("source_context", "equals", "System (handoff)"),
+        ],
+    )];
+
+    let report = explain_rules(&rules, &facts("https://app.example.com/Docs/intro"));
+    let conditions = &report[0].conditions;
+    assert_eq!(
+        conditions[0].subject.as_deref(),
+        Some("app.example.com/docs/intro")
+    );
+    assert!(conditions[0].matched);
+    assert_eq!(conditions[1].subject.as_deref(), Some("fri"));
+    assert!(!conditions[1].matched);
+    assert_eq!(conditions[2].subject.as_deref(), Some("10:30"));
+    assert!(conditions[2].matched);
+    assert_eq!(conditions[3].subject.as_deref(), Some(""));
+    assert_eq!(report[0].status, RuleStatus::NotMatched);
+    assert_eq!(
+        report[0].reason,
+        "weekday in 'sat,sun' did not match 'fri'."
+    );
+}
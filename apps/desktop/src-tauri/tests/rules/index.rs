@@ -0,0 +1,92 @@
+use desktop_lib::domain::models::{Action, Condition, Rule, RulePolicy};
+use desktop_lib::rules::{find_matching_rule, LinkFacts, RuleIndex};
+
+fn url_rule(id: &str, priority: i32, operator: &str, pattern: &str) -> Rule {
+    Rule {
+        id: id.to_string(),
+        rule_name: pattern.to_string(),
+        priority,
+        conditions: vec![Condition {
+            fact: "url".to_string(),
+            operator: operator.to_string(),
+            value: pattern.to_string(),
+        }],
+        action: Action {
+            profile: String::new(),
+            browser: "firefox__default".to_string(),
+            url: String::new(),
+            policy: RulePolicy::Always,
+        },
+        enabled: true,
+    }
+}
+
+fn sample_rules() -> Vec<Rule> {
+    vec![
+        url_rule(
+            "regex-docs",
+            0,
+            "regex",
+            r"^https://docs\.example\.com/private/",
+        ),
+        url_rule("exact-docs", 1, "host", "docs.example.com"),
+        url_rule("sub-example", 2, "wildcard", "*.example.com"),
+        url_rule("partial-label", 3, "wildcard", "*ample.org"),
+        url_rule("path-only", 4, "wildcard", "*/download/*"),
+        url_rule("host-path", 5, "wildcard", "github.com/acme/*"),
+        url_rule("idn", 6, "host", "bücher.example"),
+        url_rule("invalid-regex", 7, "regex", "(unclosed"),
+    ]
+}
+
+#[test]
+fn index_agrees_with_linear_evaluation() {
+    let rules = sample_rules();
+    let index = RuleIndex::build(&rules);
+
+    for url in [
+        "https://docs.example.com/private/plan",
+        "https://docs.example.com/public",
+        "https://api.example.com/v1",
+        "https://example.com/",
+        "https://sample.org/",
+        "https://files.test/download/setup.exe",
+        "https://github.com/acme/widgets",
+        "https://github.com/other/widgets",
+        "https://xn--bcher-kva.example/",
+        "https://unmatched.test/",
+    ] {
+        let facts = LinkFacts::from_url(url).expect("valid url");
+        let indexed = index.find(&facts).map(|rule| rule.id.as_str());
+        let linear = find_matching_rule(&rules, &facts).map(|rule| rule.id.as_str());
+        assert_eq!(indexed, linear, "mismatch for {url}");
+    }
+}
+
+#[test]
+fn index_prefers_lower_priority_across_buckets() {
+    let rules = vec![
+        url_rule("host", 3, "host", "app.example.com"),
+        url_rule("wildcard", 2, "wildcard", "*.example.com"),
+        url_rule("regex", 1, "regex", "example"),
+    ];
+    let index = RuleIndex::build(&rules);
+    let facts = LinkFacts::from_url("https://app.example.com/").expect("valid url");
+    assert_eq!(index.find(&facts).map(|r| r.id.as_str()), Some("regex"));
+}
+
+#[test]
+fn index_skips_disabled_and_uncompilable_rules() {
+    let mut disabled = url_rule("disabled", 0, "host", "example.com");
+    disabled.enabled = false;
+    let rules = vec![
+        disabled,
+        url_rule("broken", 1, "regex", "(unclosed"),
+        url_rule("fallback", 2, "wildcard", "*example.com"),
+    ];
+    let index = RuleIndex::build(&rules);
+    assert_eq!(index.len(), 2);
+
+    let facts = LinkFacts::from_url("https://example.com/").expect("valid url");
+    assert_eq!(index.find(&facts).map(|r| r.id.as_str()), Some("fallback"));
+}
@@ -0,0 +1,128 @@
+use desktop_lib::rules::{lint_rules, BrowserInventory, LintCode, RuleLint, RulesSnapshot};
+use serde_json::{json, Value};
+
+fn snapshot(domain_rules: Vec<Value>, file_type_rules: Vec<Value>) -> RulesSnapshot {
+    serde_json::from_value(json!({
+        "domainRules": domain_rules,
+        "fileTypeRules": file_type_rules,
+    }))
+    .expect("snapshot")
+}
+
+fn inventory() -> BrowserInventory {
+    BrowserInventory {
+        browsers: vec!["google-chrome".to_string(), "firefox".to_string()],
+        ids: vec![
+            "google-chrome__default".to_string(),
+            "google-chrome__profile-1".to_string(),
+            "firefox__default".to_string(),
+        ],
+    }
+}
+
+fn codes(lints: &[RuleLint]) -> Vec<(&str, LintCode, Option<&str>)> {
+    lints
+        .iter()
+        .map(|lint| {
+            (
+                lint.rule_id.as_str(),
+                lint.code,
+                lint.related_rule_id.as_deref(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn broader_earlier_patterns_shadow_later_rules() {
+    let rules = snapshot(
+        vec![
+            json!({ "id": "all-github", "pattern": "*.github.com", "matchType": "wildcard" }),
+            json!({ "id": "gist", "pattern": "gist.github.com", "matchType": "host" }),
+            json!({ "id": "org", "pattern": "*.github.com/acme/*", "matchType": "wildcard" }),
+            json!({ "id": "apex", "pattern": "github.com", "matchType": "host" }),
+            json!({ "id": "work-docs", "pattern": "docs.example.com/*", "matchType": "wildcard" }),
+            json!({ "id": "docs", "pattern": "docs.example.com", "matchType": "host" }),
+        ],
+        vec![],
+    );
+
+    assert_eq!(
+        codes(&lint_rules(&rules, &inventory())),
+        vec![
+            ("gist", LintCode::Shadowed, Some("all-github")),
+            ("org", LintCode::Shadowed, Some("all-github")),
+        ]
+    );
+}
+
+#[test]
+fn extra_conditions_only_shadow_when_the_later_rule_has_them_too() {
+    let slack = json!({ "fact": "source_app", "operator": "equals", "value": "Slack" });
+    let rules = snapshot(
+        vec![
+            json!({ "id": "from-slack", "pattern": "*", "matchType": "wildcard", "conditions": [slack] }),
+            json!({ "id": "zoom", "pattern": "zoom.us", "matchType": "host" }),
+            json!({ "id": "zoom-from-slack", "pattern": "zoom.us", "matchType": "host", "conditions": [slack] }),
+        ],
+        vec![],
+    );
+
+    assert_eq!(
+        codes(&lint_rules(&rules, &inventory())),
+        vec![("zoom-from-slack", LintCode::Shadowed, Some("from-slack"))]
+    );
+}
+
+#[test]
+fn identical_rules_are_duplicates_or_conflicts() {
+    let rules = snapshot(
+        vec![
+            json!({ "id": "a", "pattern": "Example.com", "matchType": "host", "browserId": "firefox__default" }),
+            json!({ "id": "b", "pattern": "example.com", "matchType": "host", "browserId": "firefox__default" }),
+            json!({ "id": "c", "pattern": "example.com", "matchType": "host", "browserId": "google-chrome__default" }),
+            json!({ "id": "off", "pattern": "example.com", "matchType": "host", "enabled": false }),
+        ],
+        vec![
+            json!({ "id": "pdf", "extension": ".pdf", "browserId": "firefox__default" }),
+            json!({ "id": "pdf-again", "extension": ".pdf", "browserId": "google-chrome__default" }),
+        ],
+    );
+
+    assert_eq!(
+        codes(&lint_rules(&rules, &inventory())),
+        vec![
+            ("b", LintCode::Duplicate, Some("a")),
+            ("c", LintCode::Conflict, Some("a")),
+            ("pdf-again", LintCode::Conflict, Some("pdf")),
+        ]
+    );
+}
+
+#[test]
+fn reports_missing_browsers_profiles_and_invalid_conditions() {
+    let rules = snapshot(
+        vec![
+            json!({ "id": "ok", "pattern": "a.example", "browserId": "google-chrome__profile-1" }),
+            json!({ "id": "profile", "pattern": "b.example", "browserId": "google-chrome__profile-9" }),
+            json!({ "id": "browser", "pattern": "c.example", "browserId": "vivaldi__default" }),
+            json!({ "id": "regex", "pattern": "(", "matchType": "regex" }),
+        ],
+        vec![json!({ "id": "pdf", "extension": ".pdf", "browserId": "opera" })],
+    );
+
+    let lints = lint_rules(&rules, &inventory());
+    assert_eq!(
+        codes(&lints),
+        vec![
+            ("regex", LintCode::Invalid, None),
+            ("profile", LintCode::MissingProfile, None),
+            ("browser", LintCode::MissingBrowser, None),
+            ("pdf", LintCode::MissingBrowser, None),
+        ]
+    );
+    assert_eq!(
+        lints[1].message,
+        "Profile 'profile-9' was not found for browser 'google-chrome'."
+    );
+}
@@ -0,0 +1,270 @@
+use desktop_lib::domain::models::{Action, Condition, Rule, RulePolicy};
+use desktop_lib::rules::matching::wildcard_matches;
+use desktop_lib::rules::validation::normalize_file_type_rule;
+use desktop_lib::rules::{find_matching_rule, LinkFacts, RuleIndex, RulesSnapshot};
+
+fn url_rule(id: &str, priority: i32, operator: &str, pattern: &str) -> Rule {
+    Rule {
+        id: id.to_string(),
+        rule_name: pattern.to_string(),
+        priority,
+        conditions: vec![Condition {
+            fact: "url".to_string(),
+            operator: operator.to_string(),
+            value: pattern.to_string(),
+        }],
+        action: Action {
+            profile: String::new(),
+            browser: "google-chrome__default".to_string(),
+            url: String::new(),
+            policy: RulePolicy::Always,
+        },
+        enabled: true,
+    }
+}
+
+fn matched_id(rules: &[Rule], url: &str) -> Option<String> {
+    let facts = LinkFacts::from_url(url).expect("valid url");
+    find_matching_rule(rules, &facts).map(|rule| rule.id.clone())
+}
+
+#[test]
+fn host_rules_match_exact_host_only() {
+    let rules = vec![url_rule("github", 0, "host", "GitHub.com")];
+    assert_eq!(
+        matched_id(&rules, "https://github.com/org/repo"),
+        Some("github".to_string())
+    );
+    assert_eq!(matched_id(&rules, "https://gist.github.com/"), None);
+}
+
+#[test]
+fn wildcard_rules_cover_subdomains_and_paths() {
+    let rules = vec![
+        url_rule("figma-files", 0, "wildcard", "*.figma.com/files/*"),
+        url_rule("atlassian", 1, "wildcard", "*.atlassian.net"),
+    ];
+    assert_eq!(
+        matched_id(&rules, "https://www.figma.com/files/abc"),
+        Some("figma-files".to_string())
+    );
+    assert_eq!(matched_id(&rules, "https://www.figma.com/proto/abc"), None);
+    assert_eq!(
+        matched_id(&rules, "https://team.atlassian.net/browse/X-1"),
+        Some("atlassian".to_string())
+    );
+}
+
+#[test]
+fn wildcard_rules_skip_links_without_a_host() {
+    let rules = vec![url_rule("everything", 0, "wildcard", "*")];
+    assert_eq!(
+        matched_id(&rules, "ftp://files.example.com/pub"),
+        Some("everything".to_string())
+    );
+    assert_eq!(matched_id(&rules, "mailto:someone@example.com"), None);
+    assert_eq!(matched_id(&rules, "file:///home/me/notes.html"), None);
+}
+
+#[test]
+fn regex_rules_match_the_entire_url() {
+    let rules = vec![
+        url_rule("docs", 0, "regex", r"^https://docs\.google\.com/.*/edit"),
+        url_rule("broken", 1, "regex", "(unclosed"),
+    ];
+    assert_eq!(
+        matched_id(&rules, "https://docs.google.com/document/d/1/edit"),
+        Some("docs".to_string())
+    );
+    assert_eq!(
+        matched_id(&rules, "https://docs.google.com/document/d/1/view"),
+        None
+    );
+}
+
+#[test]
+fn rules_are_evaluated_by_priority_and_skip_disabled() {
+    let mut disabled = url_rule("disabled", 0, "host", "example.com");
+    disabled.enabled = false;
+    let rules = vec![
+        url_rule("low", 5, "wildcard", "*example.com"),
+        disabled,
+        url_rule("high", 1, "host", "example.com"),
+    ];
+    assert_eq!(
+        matched_id(&rules, "https://example.com/"),
+        Some("high".to_string())
+    );
+}
+
+#[test]
+fn wildcard_matches_handles_multiple_stars() {
+    assert!(wildcard_matches(
+        "*.corp.*/wiki/*",
+        "intranet.corp.local/wiki/page"
+    ));
+    assert!(wildcard_matches("*", ""));
+    assert!(!wildcard_matches("*.corp.com", "corp.com"));
+    assert!(!wildcard_matches("a*b", "acbd"));
+}
+
+#[test]
+fn source_conditions_narrow_a_domain_rule() {
+    let mut from_chat = url_rule("chat", 0, "host", "github.com");
+    from_chat.conditions.push(Condition {
+        fact: "source_app".to_string(),
+        operator: "contains".to_string(),
+        value: "slack".to_string(),
+    });
+    let mut handoff = url_rule("handoff", 1, "host", "github.com");
+    handoff.conditions.push(Condition {
+        fact: "source_context".to_string(),
+        operator: "regex".to_string(),
+        value: r"^system \(handoff\)$".to_string(),
+    });
+    let rules = vec![from_chat, handoff, url_rule("any", 2, "host", "github.com")];
+    let index = RuleIndex::build(&rules);
+
+    let matched = |source_app: &str, source_context: Option<&str>| {
+        let facts = LinkFacts::from_url("https://github.com/")
+            .expect("valid url")
+            .with_source(source_app, source_context);
+        let linear = find_matching_rule(&rules, &facts).map(|rule| rule.id.clone());
+        assert_eq!(index.find(&facts).map(|rule| rule.id.clone()), linear);
+        linear
+    };
+
+    assert_eq!(matched("Slack Desktop", None), Some("chat".to_string()));
+    assert_eq!(
+        matched("Terminal", Some("System (handoff)")),
+        Some("handoff".to_string())
+    );
+    assert_eq!(matched("Terminal", None), Some("any".to_string()));
+}
+
+#[test]
+fn source_app_equals_is_case_insensitive_and_exact() {
+    let mut rule = url_rule("work", 0, "wildcard", "*.example.com");
+    rule.conditions.push(Condition {
+        fact: "source_app".to_string(),
+        operator: "equals".to_string(),
+        value: "Microsoft Teams".to_string(),
+    });
+    let rules = vec![rule];
+
+    let facts = |source_app: &str| {
+        LinkFacts::from_url("https://docs.example.com/")
+            .expect("valid url")
+            .with_source(source_app, None)
+    };
+    assert!(find_matching_rule(&rules, &facts("microsoft teams")).is_some());
+    assert!(find_matching_rule(&rules, &facts("Microsoft Teams (work)")).is_none());
+    assert!(find_matching_rule(&rules, &facts("")).is_none());
+}
+
+#[test]
+fn extension_comes_from_download_params_before_the_path() {
+    let extension = |url: &str| LinkFacts::from_url(url).expect("valid url").extension();
+
+    assert_eq!(
+        extension("https://example.com/docs/Report.PDF"),
+        Some(".pdf".to_string())
+    );
+    assert_eq!(
+        extension("https://example.com/download.php?filename=logo.svg"),
+        Some(".svg".to_string())
+    );
+    assert_eq!(
+        extension(
+            "https://bucket.s3.amazonaws.com/obj?response-content-disposition=attachment%3B%20filename%3D%22data.json%22"
+        ),
+        Some(".json".to_string())
+    );
+    assert_eq!(
+        extension("https://example.com/get.aspx?download=1"),
+        Some(".aspx".to_string())
+    );
+    assert_eq!(extension("https://example.com/blog/"), None);
+    assert_eq!(extension("https://example.com/.well-known"), None);
+}
+
+#[test]
+fn multi_part_extensions_match_the_end_of_the_file_name() {
+    let mut snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "fileTypeRules": [{ "id": "tarball", "extension": "*.tar.gz" }],
+    }))
+    .expect("snapshot");
+    snapshot.file_type_rules = snapshot
+        .file_type_rules
+        .into_iter()
+        .map(|rule| normalize_file_type_rule(rule).expect("valid extension"))
+        .collect();
+    let rules = snapshot.to_rules();
+
+    for url in [
+        "https://example.com/releases/app-1.2.TAR.GZ",
+        "https://example.com/get?filename=app.tar.gz",
+    ] {
+        assert_eq!(
+            matched_id(&rules, url),
+            Some("tarball".to_string()),
+            "{url}"
+        );
+    }
+    for url in [
+        "https://example.com/releases/app.gz",
+        "https://example.com/releases/.tar.gz",
+        "https://example.com/releases/",
+    ] {
+        assert_eq!(matched_id(&rules, url), None, "{url}");
+    }
+}
+
+#[test]
+fn file_type_rules_route_local_files() {
+    let mut snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [{ "id": "everything", "pattern": "*", "matchType": "wildcard" }],
+        "fileTypeRules": [{ "id": "html", "extension": "html" }],
+    }))
+    .expect("snapshot");
+    snapshot.file_type_rules = snapshot
+        .file_type_rules
+        .into_iter()
+        .map(|rule| normalize_file_type_rule(rule).expect("valid extension"))
+        .collect();
+    let rules = snapshot.to_rules();
+    let index = RuleIndex::build(&rules);
+
+    let url = "file:///home/me/project/build/index.html";
+    assert_eq!(matched_id(&rules, url), Some("html".to_string()));
+    let facts = LinkFacts::from_url(url).expect("valid url");
+    assert_eq!(
+        index.find(&facts).map(|rule| rule.id.as_str()),
+        Some("html")
+    );
+    assert_eq!(matched_id(&rules, "file:///home/me/project/notes.md"), None);
+}
+
+#[test]
+fn file_type_rules_take_precedence_over_domain_rules() {
+    let snapshot: desktop_lib::rules::RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [{ "id": "github", "pattern": "github.com", "matchType": "host" }],
+        "fileTypeRules": [{ "id": "pdf", "extension": ".pdf" }],
+    }))
+    .expect("snapshot");
+    let rules = snapshot.to_rules();
+    let index = RuleIndex::build(&rules);
+
+    for url in [
+        "https://github.com/org/repo/raw/main/spec.pdf",
+        "https://example.com/file?filename=spec.pdf",
+    ] {
+        let facts = LinkFacts::from_url(url).expect("valid url");
+        assert_eq!(matched_id(&rules, url), Some("pdf".to_string()));
+        assert_eq!(index.find(&facts).map(|rule| rule.id.as_str()), Some("pdf"));
+    }
+    assert_eq!(
+        matched_id(&rules, "https://github.com/org/repo"),
+        Some("github".to_string())
+    );
+}
@@ -0,0 +1,77 @@
+use desktop_lib::rules::rewrite::validate_template;
+use desktop_lib::rules::validation::normalize_domain_rule;
+use desktop_lib::rules::{rewrite_url, DomainMatchType, DomainRule, LinkFacts, RuleIndex};
+
+fn domain_rule(pattern: &str, match_type: &str, rewrite: &str) -> DomainRule {
+    let rule = serde_json::from_value(serde_json::json!({
+        "id": "rule-1",
+        "pattern": pattern,
+        "matchType": match_type,
+        "rewrite": rewrite,
+    }))
+    .expect("domain rule");
+    normalize_domain_rule(rule).expect("valid rule")
+}
+
+fn rewritten(rule: &DomainRule, url: &str) -> Option<Result<String, String>> {
+    let facts = LinkFacts::from_url(url).expect("valid url");
+    rewrite_url(&rule.to_rule(0), &facts)
+}
+
+#[test]
+fn wildcard_placeholders_match_and_carry_into_the_template() {
+    let rule = domain_rule(
+        "jira.old.corp/browse/{key}",
+        "wildcard",
+        "new.atlassian.net/browse/{key}?from={host}",
+    );
+    let facts = LinkFacts::from_url("https://jira.old.corp/browse/OPS-42").expect("valid url");
+    let index = RuleIndex::build(&[rule.to_rule(0)]);
+    assert_eq!(index.find(&facts).map(|r| r.id.as_str()), Some("rule-1"));
+
+    assert_eq!(
+        rewritten(&rule, "https://jira.old.corp/browse/OPS-42"),
+        Some(Ok(
+            "https://new.atlassian.net/browse/OPS-42?from=jira.old.corp".to_string()
+        ))
+    );
+}
+
+#[test]
+fn regex_and_star_captures_are_numbered() {
+    let regex = domain_rule(
+        r"^https://(?P<org>[^.]+)\.slack\.com/archives/(\w+)",
+        "regex",
+        "slack://channel?team={org}&id={2}",
+    );
+    assert_eq!(
+        rewritten(&regex, "https://acme.slack.com/archives/C123"),
+        Some(Ok("slack://channel?team=acme&id=C123".to_string()))
+    );
+
+    let wildcard = domain_rule("*.example.com/*", "wildcard", "https://mirror.test/{1}/{2}");
+    assert_eq!(
+        rewritten(&wildcard, "http://docs.example.com/a/b?x=1"),
+        Some(Ok("https://mirror.test/docs/a/b".to_string()))
+    );
+    assert_eq!(
+        rewritten(
+            &domain_rule("example.com", "host", ""),
+            "https://example.com"
+        ),
+        None
+    );
+}
+
+#[test]
+fn templates_may_only_use_captures_the_pattern_provides() {
+    assert!(validate_template("{url}", DomainMatchType::Host, "example.com").is_ok());
+    let err = validate_template("x.test/{key}", DomainMatchType::Host, "example.com").unwrap_err();
+    assert_eq!(
+        err,
+        "Rewrite template references unknown placeholder '{key}'."
+    );
+    assert!(validate_template("x.test/{2}", DomainMatchType::Wildcard, "*.a.test/*").is_ok());
+    assert!(validate_template("x.test/{3}", DomainMatchType::Wildcard, "*.a.test/*").is_err());
+    assert!(validate_template("{id}", DomainMatchType::Wildcard, "a.test/{id}/{id}").is_err());
+}
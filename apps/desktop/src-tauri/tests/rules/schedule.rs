@@ -0,0 +1,117 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use desktop_lib::domain::models::{Action, Condition, Rule, RulePolicy};
+use desktop_lib::rules::schedule::Schedule;
+use desktop_lib::rules::{find_matching_rule, LinkFacts};
+
+fn at(value: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").expect("valid timestamp")
+}
+
+fn condition(fact: &str, operator: &str, value: &str) -> Condition {
+    Condition {
+        fact: fact.to_string(),
+        operator: operator.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn weekday_sets_accept_lists_and_wrapping_ranges() {
+    let workweek = Schedule::parse("weekday", "in", "mon-fri").expect("valid");
+    // 2026-10-16 is a Friday.
+    assert!(workweek.matches(at("2026-10-16 12:00")));
+    assert!(!workweek.matches(at("2026-10-17 12:00")));
+
+    let weekend = Schedule::parse("weekday", "in", "Saturday, sun").expect("valid");
+    assert!(weekend.matches(at("2026-10-18 08:00")));
+    assert!(!weekend.matches(at("2026-10-19 08:00")));
+
+    let wrapping = Schedule::parse("weekday", "in", "fri-mon").expect("valid");
+    assert!(wrapping.matches(at("2026-10-19 08:00")));
+    assert!(!wrapping.matches(at("2026-10-20 08:00")));
+
+    assert!(Schedule::parse("weekday", "in", "mon,funday").is_err());
+    assert!(Schedule::parse("weekday", "between", "mon-fri").is_err());
+}
+
+#[test]
+fn time_windows_exclude_the_end_and_may_wrap_midnight() {
+    let office = Schedule::parse("time", "between", "09:00-17:30").expect("valid");
+    assert!(office.matches(at("2026-10-16 09:00")));
+    assert!(office.matches(at("2026-10-16 17:29")));
+    assert!(!office.matches(at("2026-10-16 17:30")));
+
+    let evening = Schedule::parse("time", "between", "18:00-02:00").expect("valid");
+    assert!(evening.matches(at("2026-10-16 23:15")));
+    assert!(evening.matches(at("2026-10-17 01:59")));
+    assert!(!evening.matches(at("2026-10-17 12:00")));
+
+    assert!(Schedule::parse("time", "between", "9am-5pm").is_err());
+    assert!(Schedule::parse("time", "between", "09:00-09:00").is_err());
+}
+
+#[test]
+fn date_ranges_are_inclusive_and_open_ended() {
+    let quarter = Schedule::parse("date", "between", "2026-10-01..2026-12-31").expect("valid");
+    assert!(quarter.matches(at("2026-12-31 23:59")));
+    assert!(!quarter.matches(at("2027-01-01 00:00")));
+
+    let from = Schedule::parse("date", "between", "2026-11-01..").expect("valid");
+    assert_eq!(
+        from,
+        Schedule::DateRange {
+            start: NaiveDate::from_ymd_opt(2026, 11, 1),
+            end: None,
+        }
+    );
+    assert!(from.matches(at("2030-01-01 00:00")));
+
+    let single = Schedule::parse("date", "between", "2026-12-25").expect("valid");
+    assert!(single.matches(at("2026-12-25 10:00")));
+    assert!(!single.matches(at("2026-12-26 10:00")));
+
+    assert!(Schedule::parse("date", "between", "2026-12-31..2026-01-01").is_err());
+    assert!(Schedule::parse("date", "between", "..").is_ok());
+}
+
+#[test]
+fn rules_route_by_schedule_at_evaluation_time() {
+    let rule = |id: &str, priority: i32, schedule: Vec<Condition>| {
+        let mut conditions = vec![condition("url", "wildcard", "*.atlassian.net")];
+        conditions.extend(schedule);
+        Rule {
+            id: id.to_string(),
+            rule_name: id.to_string(),
+            priority,
+            conditions,
+            action: Action {
+                profile: String::new(),
+                browser: String::new(),
+                url: String::new(),
+                policy: RulePolicy::Always,
+            },
+            enabled: true,
+        }
+    };
+    let rules = vec![
+        rule(
+            "work",
+            0,
+            vec![
+                condition("weekday", "in", "mon-fri"),
+                condition("time", "between", "09:00-18:00"),
+            ],
+        ),
+        rule("personal", 1, Vec::new()),
+    ];
+
+    let routed = |when: &str| {
+        let facts = LinkFacts::from_url("https://acme.atlassian.net/browse/OWB-1")
+            .expect("valid url")
+            .with_time(at(when));
+        find_matching_rule(&rules, &facts).map(|rule| rule.id.clone())
+    };
+    assert_eq!(routed("2026-10-16 10:00"), Some("work".to_string()));
+    assert_eq!(routed("2026-10-16 19:00"), Some("personal".to_string()));
+    assert_eq!(routed("2026-10-17 10:00"), Some("personal".to_string()));
+}
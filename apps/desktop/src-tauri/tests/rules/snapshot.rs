@@ -0,0 +1,134 @@
+use desktop_lib::rules::{RuleInput, RuleKind, RulesSnapshot};
+
+fn snapshot_with(ids: &[&str]) -> RulesSnapshot {
+    let rules = ids
+        .iter()
+        .map(|id| serde_json::json!({ "id": id, "pattern": format!("{id}.example") }))
+        .collect::<Vec<_>>();
+    serde_json::from_value(serde_json::json!({ "domainRules": rules })).expect("snapshot")
+}
+
+fn domain_ids(snapshot: &RulesSnapshot) -> Vec<&str> {
+    snapshot
+        .domain_rules
+        .iter()
+        .map(|rule| rule.id.as_str())
+        .collect()
+}
+
+#[test]
+fn upsert_replaces_in_place_or_appends() {
+    let mut snapshot = snapshot_with(&["a", "b"]);
+    let input: RuleInput = serde_json::from_value(serde_json::json!({
+        "kind": "domain",
+        "id": "a",
+        "pattern": "changed.example",
+    }))
+    .expect("rule input");
+    snapshot.upsert(input);
+
+    let input: RuleInput = serde_json::from_value(serde_json::json!({
+        "kind": "fileType",
+        "id": "pdf",
+        "extension": ".pdf",
+    }))
+    .expect("rule input");
+    snapshot.upsert(input);
+
+    assert_eq!(domain_ids(&snapshot), vec!["a", "b"]);
+    assert_eq!(snapshot.domain_rules[0].pattern, "changed.example");
+    assert_eq!(snapshot.file_type_rules.len(), 1);
+}
+
+#[test]
+fn remove_reports_missing_ids() {
+    let mut snapshot = snapshot_with(&["a", "b"]);
+    assert!(snapshot.remove("a"));
+    assert!(!snapshot.remove("a"));
+    assert_eq!(domain_ids(&snapshot), vec!["b"]);
+}
+
+#[test]
+fn reorder_requires_a_full_permutation() {
+    let mut snapshot = snapshot_with(&["a", "b", "c"]);
+    let ids = ["c", "a", "b"].map(String::from);
+    snapshot.reorder(RuleKind::Domain, &ids).expect("reorder");
+    assert_eq!(domain_ids(&snapshot), vec!["c", "a", "b"]);
+
+    let duplicate = ["c", "c", "b"].map(String::from);
+    assert!(snapshot.reorder(RuleKind::Domain, &duplicate).is_err());
+    assert!(snapshot.reorder(RuleKind::Domain, &ids[..2]).is_err());
+    assert_eq!(domain_ids(&snapshot), vec!["c", "a", "b"]);
+
+    let compiled = snapshot.to_rules();
+    assert_eq!(compiled[0].id, "c");
+    assert_eq!(compiled[0].priority, 0);
+}
+
+#[test]
+fn workspace_rules_apply_alongside_shared_ones() {
+    let snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [
+            { "id": "shared", "pattern": "github.com" },
+            { "id": "office", "pattern": "jira.example.com", "workspace": "Office" },
+            { "id": "weekend", "pattern": "youtube.com", "workspace": "Weekend" },
+        ],
+        "fileTypeRules": [{ "id": "pdf", "extension": ".pdf", "workspace": "Office" }],
+    }))
+    .expect("snapshot");
+
+    let ids = |workspace: Option<&str>| {
+        snapshot
+            .to_workspace_rules(workspace)
+            .into_iter()
+            .map(|rule| (rule.id, rule.priority))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(None), vec![("shared".to_string(), 0)]);
+    assert_eq!(
+        ids(Some("Office")),
+        vec![
+            ("pdf".to_string(), -1),
+            ("shared".to_string(), 0),
+            ("office".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn browser_ids_are_rewritten_in_both_rule_lists() {
+    let mut snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [
+            { "id": "a", "pattern": "a.example", "browserId": "firefox-web-browser__default" },
+            { "id": "b", "pattern": "b.example" },
+            { "id": "c", "pattern": "c.example", "browserId": "chrome__profile-1" },
+        ],
+        "fileTypeRules": [
+            { "id": "pdf", "extension": ".pdf", "browserId": "firefox-web-browser__work" },
+        ],
+    }))
+    .expect("snapshot");
+
+    snapshot.rewrite_browser_ids(|id| {
+        id.strip_prefix("firefox-web-browser__")
+            .map(|profile| format!("desktop-firefox__{profile}"))
+    });
+
+    let browser_ids = snapshot
+        .domain_rules
+        .iter()
+        .map(|rule| rule.browser_id.as_deref())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        browser_ids,
+        vec![
+            Some("desktop-firefox__default"),
+            None,
+            Some("chrome__profile-1")
+        ]
+    );
+    assert_eq!(
+        snapshot.file_type_rules[0].browser_id.as_deref(),
+        Some("desktop-firefox__work")
+    );
+}
@@ -0,0 +1,56 @@
+use desktop_lib::rules::{RuleHit, RuleKind, RuleStats, RulesSnapshot};
+
+#[test]
+fn report_lists_every_rule_with_its_hits() {
+    let snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [
+            { "id": "github", "pattern": "github.com" },
+            { "id": "never", "pattern": "example.com" },
+        ],
+        "fileTypeRules": [{ "id": "pdf", "extension": ".pdf" }],
+    }))
+    .expect("snapshot");
+
+    let mut stats = RuleStats::default();
+    stats.record("github", "https://github.com/a", "2024-05-01T10:00:00Z");
+    stats.record("github", "https://github.com/b", "2024-05-02T10:00:00Z");
+    stats.record("deleted", "https://old.test/", "2024-05-02T10:00:00Z");
+
+    let report = stats.report(&snapshot);
+    let ids: Vec<_> = report
+        .iter()
+        .map(|entry| (entry.rule_id.as_str(), entry.kind, entry.hit.count))
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            ("github", RuleKind::Domain, 2),
+            ("never", RuleKind::Domain, 0),
+            ("pdf", RuleKind::FileType, 0),
+        ]
+    );
+    assert_eq!(
+        report[0].hit,
+        RuleHit {
+            count: 2,
+            last_matched_at: Some("2024-05-02T10:00:00Z".to_string()),
+            last_matched_url: Some("https://github.com/b".to_string()),
+        }
+    );
+
+    assert!(stats.forget("deleted"));
+    assert!(!stats.forget("deleted"));
+}
+
+#[test]
+fn stats_round_trip_as_a_map_of_rule_ids() {
+    let mut stats = RuleStats::default();
+    stats.record("pdf", "https://example.com/a.pdf", "2024-05-01T10:00:00Z");
+
+    let value = serde_json::to_value(&stats).expect("serialize");
+    assert_eq!(value["pdf"]["count"], 1);
+    assert_eq!(value["pdf"]["lastMatchedUrl"], "https://example.com/a.pdf");
+
+    let restored: RuleStats = serde_json::from_value(value).expect("deserialize");
+    assert_eq!(restored.get("pdf").map(|hit| hit.count), Some(1));
+}
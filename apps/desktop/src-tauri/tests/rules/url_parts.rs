@@ -0,0 +1,78 @@
+use desktop_lib::rules::url_parts::UrlPart;
+use desktop_lib::rules::{find_matching_rule, LinkFacts, RulesSnapshot};
+use url::Url;
+
+fn matches(fact: &str, operator: &str, value: &str, url: &str) -> bool {
+    let part = UrlPart::parse(fact, operator, value).expect("valid condition");
+    part.matches(&Url::parse(url).expect("valid url"))
+}
+
+#[test]
+fn path_conditions_split_a_host_between_rules() {
+    let snapshot: RulesSnapshot = serde_json::from_value(serde_json::json!({
+        "domainRules": [
+            {
+                "id": "work",
+                "pattern": "github.com",
+                "conditions": [{ "fact": "path", "operator": "prefix", "value": "/our-org" }],
+            },
+            { "id": "personal", "pattern": "github.com" },
+        ],
+    }))
+    .expect("snapshot");
+    let rules = snapshot.to_rules();
+    let matched = |url: &str| {
+        let facts = LinkFacts::from_url(url).expect("valid url");
+        find_matching_rule(&rules, &facts).map(|rule| rule.id.clone())
+    };
+
+    assert_eq!(
+        matched("https://github.com/Our-Org/repo"),
+        Some("work".into())
+    );
+    assert_eq!(matched("https://github.com/our-org"), Some("work".into()));
+    assert_eq!(
+        matched("https://github.com/our-organ"),
+        Some("personal".into())
+    );
+    assert_eq!(
+        matched("https://github.com/torvalds"),
+        Some("personal".into())
+    );
+}
+
+#[test]
+fn path_globs_and_query_parameters() {
+    let url = "https://example.com/docs/v2/intro?utm_source=mail&ref=news-42&empty=";
+    assert!(matches("path", "glob", "/docs/*/intro", url));
+    assert!(!matches("path", "glob", "/docs/*/setup", url));
+    assert!(matches("path", "regex", r"^/docs/v\d+/", url));
+
+    assert!(matches("query", "present", "ref", url));
+    assert!(!matches("query", "present", "REF", url));
+    assert!(matches("query", "equals", "utm_source=mail", url));
+    assert!(matches("query", "equals", "empty=", url));
+    assert!(!matches("query", "equals", "utm_source=web", url));
+    assert!(matches("query", "regex", r"ref=^news-\d+$", url));
+
+    assert!(UrlPart::parse("query", "equals", "=mail").is_err());
+    assert!(UrlPart::parse("path", "equals", "/docs").is_err());
+}
+
+#[test]
+fn port_and_scheme_use_known_defaults() {
+    assert!(matches("port", "equals", "443", "https://example.com/"));
+    assert!(matches("port", "equals", "8080", "http://localhost:8080/"));
+    assert!(!matches("port", "equals", "80", "http://localhost:8080/"));
+    assert!(matches(
+        "scheme",
+        "equals",
+        "HTTP://",
+        "http://example.com/"
+    ));
+    assert!(!matches("scheme", "equals", "https", "http://example.com/"));
+
+    assert!(UrlPart::parse("port", "equals", "0").is_err());
+    assert!(UrlPart::parse("port", "equals", "https").is_err());
+    assert!(UrlPart::parse("scheme", "equals", "1http").is_err());
+}
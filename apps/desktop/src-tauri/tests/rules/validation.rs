@@ -0,0 +1,81 @@
+use desktop_lib::domain::models::Condition;
+use desktop_lib::rules::validation::{
+    normalize_condition, normalize_domain_rule, normalize_extension, validate_pattern,
+};
+use desktop_lib::rules::{DomainMatchType, DomainRule};
+
+fn domain_rule(pattern: &str) -> DomainRule {
+    serde_json::from_value(serde_json::json!({
+        "id": "rule-1",
+        "domain": pattern,
+        "browserId": "  ",
+    }))
+    .expect("domain rule")
+}
+
+#[test]
+fn host_patterns_must_be_bare_hosts() {
+    assert!(validate_pattern(DomainMatchType::Host, "github.com").is_ok());
+    assert!(validate_pattern(DomainMatchType::Host, "bücher.example").is_ok());
+    assert!(validate_pattern(DomainMatchType::Host, "").is_err());
+    assert!(validate_pattern(DomainMatchType::Host, "https://github.com").is_err());
+    assert!(validate_pattern(DomainMatchType::Host, "github.com/org").is_err());
+    assert!(validate_pattern(DomainMatchType::Host, "*.github.com").is_err());
+    assert!(validate_pattern(DomainMatchType::Host, "git hub.com").is_err());
+}
+
+#[test]
+fn wildcard_patterns_allow_stars_in_host_and_path() {
+    assert!(validate_pattern(DomainMatchType::Wildcard, "*.figma.com/files/*").is_ok());
+    assert!(validate_pattern(DomainMatchType::Wildcard, "*/docs/*").is_ok());
+    assert!(validate_pattern(DomainMatchType::Wildcard, "*").is_err());
+    assert!(validate_pattern(DomainMatchType::Wildcard, "https://*.figma.com").is_err());
+    assert!(validate_pattern(DomainMatchType::Wildcard, "*.exa mple.com").is_err());
+}
+
+#[test]
+fn regex_patterns_must_compile() {
+    assert!(validate_pattern(DomainMatchType::Regex, r"^https://(www\.)?example\.com/").is_ok());
+    let err = validate_pattern(DomainMatchType::Regex, "(unclosed").unwrap_err();
+    assert!(err.contains("Invalid regular expression"));
+}
+
+#[test]
+fn normalize_domain_rule_canonicalises_legacy_fields() {
+    let rule = normalize_domain_rule(domain_rule(" *.slack.com ")).expect("valid rule");
+    assert_eq!(rule.pattern, "*.slack.com");
+    assert_eq!(rule.domain, None);
+    assert_eq!(rule.match_type, Some(DomainMatchType::Wildcard));
+    assert_eq!(rule.browser_id, None);
+    assert!(rule.enabled);
+}
+
+#[test]
+fn normalize_extension_accepts_common_spellings() {
+    assert_eq!(normalize_extension("pdf").unwrap(), ".pdf");
+    assert_eq!(normalize_extension(" .SVG ").unwrap(), ".svg");
+    assert_eq!(normalize_extension("*.tar.gz").unwrap(), ".tar.gz");
+    assert!(normalize_extension("").is_err());
+    assert!(normalize_extension("p df").is_err());
+}
+
+#[test]
+fn normalize_condition_checks_fact_operator_and_value() {
+    let condition = |fact: &str, operator: &str, value: &str| Condition {
+        fact: fact.to_string(),
+        operator: operator.to_string(),
+        value: value.to_string(),
+    };
+
+    let normalized =
+        normalize_condition(condition(" source_app ", "Contains", " Slack ")).expect("valid");
+    assert_eq!(normalized.fact, "source_app");
+    assert_eq!(normalized.operator, "contains");
+    assert_eq!(normalized.value, "Slack");
+
+    assert!(normalize_condition(condition("source_context", "regex", "^System")).is_ok());
+    assert!(normalize_condition(condition("source_context", "regex", "(")).is_err());
+    assert!(normalize_condition(condition("source_app", "startsWith", "Slack")).is_err());
+    assert!(normalize_condition(condition("referrer", "equals", "Slack")).is_err());
+    assert!(normalize_condition(condition("source_app", "equals", "  ")).is_err());
+}
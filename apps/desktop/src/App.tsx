@@ -5,7 +5,7 @@ import Rules from './pages/Rules';
 import Settings from './pages/Settings';
 import type { BrowserProfile } from './OpenWithDialog';
 import {
-  fetchAvailableBrowsers,
+  fetchBrowserDescriptors,
   fetchRoutingSnapshot,
   listenIncomingLink,
   listenLaunchDecision,
@@ -331,23 +331,22 @@ export default function App() {
         .replace(/[^a-z0-9]+/g, '-')
         .replace(/^-+|-+$/g, '');
 
-    const browserId = (name: string, directory: string | null | undefined) => {
-      const base = normalize(name);
+    const browserId = (base: string, directory: string | null | undefined) => {
       const suffix = directory ? normalize(directory) : 'default';
       return `${base}__${suffix}`;
     };
 
     (async () => {
       try {
-        const names = await fetchAvailableBrowsers();
+        const descriptors = await fetchBrowserDescriptors();
         const catalog = new Map<string, BrowserProfile>();
 
-        for (const name of names) {
+        for (const { id: base, displayName: name } of descriptors) {
           try {
             const profiles = await fetchProfilesFor(name);
             if (profiles && profiles.length > 0) {
               profiles.forEach(profile => {
-                const id = browserId(name, profile.directory);
+                const id = browserId(base, profile.directory);
                 catalog.set(id, {
                   id,
                   name,
@@ -357,7 +356,7 @@ export default function App() {
               });
             }
 
-            const defaultId = browserId(name, null);
+            const defaultId = browserId(base, null);
             if (!catalog.has(defaultId)) {
               catalog.set(defaultId, {
                 id: defaultId,
@@ -369,7 +368,7 @@ export default function App() {
           } catch (err) {
             // eslint-disable-next-line no-console
             console.warn(`Unable to load profiles for ${name}`, err);
-            const defaultId = browserId(name, null);
+            const defaultId = browserId(base, null);
             if (!catalog.has(defaultId)) {
               catalog.set(defaultId, {
                 id: defaultId,
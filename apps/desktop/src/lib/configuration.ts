@@ -0,0 +1,77 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { FallbackPreference } from './preferences';
+
+export type ConfigFormat = 'json' | 'toml';
+
+export type ImportMode = 'merge' | 'replace';
+
+export type RuleChange = {
+  id: string;
+  kind: 'domain' | 'fileType';
+  label: string;
+};
+
+export type PreferenceChange = {
+  name: 'tracking' | 'shortLinks' | 'schemes' | 'workspaces' | 'activeWorkspace';
+  from: unknown;
+  to: unknown;
+};
+
+export type ConfigDiff = {
+  added: RuleChange[];
+  updated: RuleChange[];
+  removed: RuleChange[];
+  unchanged: number;
+  fallback: {
+    from: FallbackPreference | null;
+    to: FallbackPreference | null;
+  } | null;
+  preferences: PreferenceChange[];
+};
+
+export type ImportReport = {
+  mode: ImportMode;
+  applied: boolean;
+  diff: ConfigDiff;
+  warnings: string[];
+};
+
+export async function exportConfiguration(format: ConfigFormat = 'json') {
+  return invoke<string>('export_configuration', { format });
+}
+
+export async function importConfiguration(input: {
+  contents: string;
+  format?: ConfigFormat;
+  mode?: ImportMode;
+  dryRun?: boolean;
+}) {
+  return invoke<ImportReport>('import_configuration', {
+    contents: input.contents,
+    format: input.format ?? null,
+    mode: input.mode ?? 'merge',
+    dryRun: input.dryRun ?? false,
+  });
+}
+
+export type UntranslatedHandler = {
+  handler: number;
+  reason: string;
+  source: string;
+};
+
+export type FinickyImportReport = ImportReport & {
+  untranslated: UntranslatedHandler[];
+};
+
+export async function importFinickyConfiguration(input: {
+  contents: string;
+  mode?: ImportMode;
+  dryRun?: boolean;
+}) {
+  return invoke<FinickyImportReport>('import_finicky_configuration', {
+    contents: input.contents,
+    mode: input.mode ?? 'merge',
+    dryRun: input.dryRun ?? false,
+  });
+}
@@ -1,17 +1,29 @@
+export type LinkWarning = {
+  kind: 'mixed-script' | 'confusable';
+  displayHost: string;
+  asciiHost: string;
+  lookalike: string | null;
+  message: string;
+};
+
 export type ActiveLink = {
   id: string;
   url: string;
+  originalUrl?: string | null;
   sourceApp: string;
   sourceContext: string;
   contactName: string;
   preview: string;
   recommendedBrowser?: BrowserSelection;
   arrivedAt: string;
+  warning?: LinkWarning | null;
 };
 
 export type LaunchHistoryItem = {
   id: string;
   url: string;
+  originalUrl?: string | null;
+  ruleId?: string | null;
   decidedAt: string;
   browser: string;
   profileLabel?: string | null;
@@ -7,11 +7,34 @@ export type FallbackProfilePreference = {
 
 export type FallbackPreference = {
   browser: string;
+  browser_id?: string | null;
   profile?: FallbackProfilePreference | null;
 };
 
+export type TrackingCleanup = {
+  enabled: boolean;
+  parameters: string[];
+  exceptions: string[];
+};
+
+export type ShortLinkExpansion = {
+  enabled: boolean;
+  shorteners: string[];
+  timeoutMs: number;
+};
+
+export type SchemeHandler = {
+  scheme: string;
+  target?: FallbackPreference | null;
+};
+
 export type PreferencesSnapshot = {
   fallback: FallbackPreference | null;
+  workspaces: string[];
+  active_workspace: string | null;
+  tracking: TrackingCleanup;
+  short_links: ShortLinkExpansion;
+  schemes: SchemeHandler[];
 };
 
 export async function fetchPreferences() {
@@ -27,3 +50,25 @@ export async function updateFallbackPreference(input: {
     profile: input.profile ?? null,
   });
 }
+
+export async function setWorkspaces(names: string[]) {
+  return invoke<PreferencesSnapshot>('set_workspaces', { names });
+}
+
+export async function setActiveWorkspace(workspace: string | null) {
+  return invoke<string | null>('set_active_workspace', { workspace });
+}
+
+export async function updateTrackingCleanup(cleanup: TrackingCleanup | null) {
+  return invoke<TrackingCleanup>('set_tracking_cleanup', { cleanup });
+}
+
+export async function updateShortLinkExpansion(
+  settings: ShortLinkExpansion | null
+) {
+  return invoke<ShortLinkExpansion>('set_short_link_expansion', { settings });
+}
+
+export async function updateSchemeHandlers(handlers: SchemeHandler[]) {
+  return invoke<SchemeHandler[]>('set_scheme_handlers', { handlers });
+}
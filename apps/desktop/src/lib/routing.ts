@@ -13,20 +13,53 @@ export type ProfileDescriptorWire = {
   directory: string;
 };
 
+export type BrowserFamily = 'chromium' | 'gecko' | 'webkit' | 'unknown';
+
+export type ReleaseChannel =
+  | 'stable'
+  | 'beta'
+  | 'dev'
+  | 'canary'
+  | 'nightly'
+  | 'esr';
+
+export type InstalledBrowserWire = {
+  id: string;
+  displayName: string;
+  family: BrowserFamily;
+  executable: string | null;
+  version: string | null;
+  channel: ReleaseChannel;
+  icon: string | null;
+};
+
+export type LinkWarningWire = {
+  kind: 'mixed-script' | 'confusable';
+  display_host: string;
+  ascii_host: string;
+  lookalike?: string | null;
+  message: string;
+};
+
 export type IncomingLinkWire = {
   id: string;
   url: string;
+  original_url?: string | null;
   source_app: string;
   source_context?: string | null;
   contact_name?: string | null;
   preview?: string | null;
   recommended_browser?: BrowserDescriptorWire | null;
   arrived_at?: string | null;
+  warning?: LinkWarningWire | null;
 };
 
 export type LaunchDecisionWire = {
   id: string;
   url: string;
+  original_url?: string | null;
+  rule_id?: string | null;
+  matched_url?: string | null;
   browser: string;
   profile_label?: string | null;
   profile_directory?: string | null;
@@ -39,6 +72,7 @@ export type LaunchDecisionWire = {
 export type RoutingSnapshotWire = {
   active: IncomingLinkWire | null;
   history: LaunchDecisionWire[];
+  active_workspace: string | null;
 };
 
 export type RoutingStatusWire = {
@@ -53,6 +87,37 @@ export type RoutingErrorWire = {
   message: string;
 };
 
+export type ConditionEvaluationWire = {
+  fact: string;
+  operator: string;
+  value: string;
+  subject: string | null;
+  matched: boolean;
+  error: string | null;
+};
+
+export type RuleEvaluationWire = {
+  ruleId: string;
+  ruleName: string;
+  priority: number;
+  status: 'selected' | 'shadowed' | 'notMatched' | 'disabled' | 'invalid';
+  reason: string;
+  conditions: ConditionEvaluationWire[];
+};
+
+export type RouteExplanationWire = {
+  input_url: string;
+  normalized_url: string;
+  source_app: string;
+  source_context: string | null;
+  evaluated_at: string;
+  rules: RuleEvaluationWire[];
+  matched_rule_id: string | null;
+  via: 'rule' | 'scheme' | 'fallback' | 'picker';
+  decision: LaunchDecisionWire | null;
+  notes: string[];
+};
+
 export function mapIncomingLink(
   wire: IncomingLinkWire | null
 ): ActiveLink | null {
@@ -68,12 +133,22 @@ export function mapIncomingLink(
   return {
     id: wire.id,
     url: wire.url,
+    originalUrl: wire.original_url ?? null,
     sourceApp: wire.source_app,
     sourceContext: wire.source_context ?? '',
     contactName: wire.contact_name ?? '',
     preview: wire.preview ?? '',
     recommendedBrowser,
     arrivedAt: wire.arrived_at ?? new Date().toISOString(),
+    warning: wire.warning
+      ? {
+          kind: wire.warning.kind,
+          displayHost: wire.warning.display_host,
+          asciiHost: wire.warning.ascii_host,
+          lookalike: wire.warning.lookalike ?? null,
+          message: wire.warning.message,
+        }
+      : null,
   };
 }
 
@@ -81,6 +156,8 @@ export function mapLaunchDecision(wire: LaunchDecisionWire): LaunchHistoryItem {
   return {
     id: wire.id,
     url: wire.url,
+    originalUrl: wire.original_url ?? null,
+    ruleId: wire.rule_id ?? null,
     decidedAt: wire.decided_at ?? new Date().toISOString(),
     browser: wire.browser,
     profileLabel: wire.profile_label ?? null,
@@ -96,6 +173,7 @@ export async function fetchRoutingSnapshot() {
   return {
     active: mapIncomingLink(snapshot.active),
     history: snapshot.history.map(mapLaunchDecision),
+    activeWorkspace: snapshot.active_workspace ?? null,
   };
 }
 
@@ -108,6 +186,7 @@ export async function resolveIncomingLink(input: {
     decision: {
       id: input.link.id,
       url: input.link.url,
+      original_url: input.link.originalUrl ?? null,
       browser: input.browser.name,
       profile_label: input.browser.profileLabel ?? null,
       profile_directory: input.browser.profileDirectory ?? null,
@@ -140,6 +219,18 @@ export async function simulateIncomingLink(payload?: {
   });
 }
 
+export async function explainRoute(input: {
+  url: string;
+  sourceApp?: string;
+  sourceContext?: string;
+}) {
+  return invoke<RouteExplanationWire>('explain_route', {
+    url: input.url,
+    sourceApp: input.sourceApp ?? null,
+    sourceContext: input.sourceContext ?? null,
+  });
+}
+
 export async function listenIncomingLink(
   callback: (link: ActiveLink) => void
 ): Promise<UnlistenFn> {
@@ -187,6 +278,10 @@ export async function fetchAvailableBrowsers() {
   return invoke<string[]>('get_available_browsers');
 }
 
+export async function fetchBrowserDescriptors() {
+  return invoke<InstalledBrowserWire[]>('list_browsers');
+}
+
 export async function fetchProfilesFor(browser: string) {
   return invoke<ProfileDescriptorWire[]>('get_profiles', {
     browserKind: browser,
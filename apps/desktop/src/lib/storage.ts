@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import { LazyStore } from '@tauri-apps/plugin-store';
 
 export type UiSettings = {
@@ -75,6 +76,37 @@ export type DomainRule = {
   latency: string;
   enabled: boolean;
   domain?: string;
+  rewrite?: string;
+  workspace?: string;
+  conditions?: RuleCondition[];
+};
+
+export type RuleConditionFact =
+  | 'source_app'
+  | 'source_context'
+  | 'weekday'
+  | 'time'
+  | 'date'
+  | 'extension'
+  | 'path'
+  | 'query'
+  | 'port'
+  | 'scheme';
+
+export type RuleConditionOperator =
+  | 'equals'
+  | 'contains'
+  | 'regex'
+  | 'in'
+  | 'between'
+  | 'prefix'
+  | 'glob'
+  | 'present';
+
+export type RuleCondition = {
+  fact: RuleConditionFact;
+  operator: RuleConditionOperator;
+  value: string;
 };
 
 export type FileTypeRule = {
@@ -83,6 +115,7 @@ export type FileTypeRule = {
   browserId: string | null;
   browserLabel: string;
   policy: RulePolicy;
+  workspace?: string;
 };
 
 export type RulesSnapshot = {
@@ -90,57 +123,107 @@ export type RulesSnapshot = {
   fileTypeRules: FileTypeRule[];
 };
 
-const DEFAULT_RULES: RulesSnapshot = {
-  domainRules: [],
-  fileTypeRules: [],
+type RuleKind = 'domain' | 'fileType';
+
+type RulesSnapshotWire = {
+  domainRules?: Record<string, unknown>[];
+  fileTypeRules?: FileTypeRule[];
 };
 
-const rulesStore = new LazyStore('routing-rules.json', {
-  defaults: DEFAULT_RULES,
-});
+async function invokeRules<T>(
+  command: string,
+  args?: Record<string, unknown>
+): Promise<T> {
+  try {
+    return await invoke<T>(command, args);
+  } catch (err) {
+    // The backend reports validation failures as plain strings.
+    throw err instanceof Error ? err : new Error(String(err));
+  }
+}
 
 export async function loadRules(): Promise<RulesSnapshot> {
-  await rulesStore.init();
-  const [domainRulesRaw, fileTypeRules] = await Promise.all([
-    rulesStore.get<Record<string, unknown>[]>('domainRules'),
-    rulesStore.get<FileTypeRule[]>('fileTypeRules'),
-  ]);
+  const snapshot = await invokeRules<RulesSnapshotWire>('list_rules');
 
   return {
-    domainRules: (domainRulesRaw ?? []).map(normalizeDomainRule),
-    fileTypeRules: fileTypeRules ?? [],
+    domainRules: (snapshot.domainRules ?? []).map(normalizeDomainRule),
+    fileTypeRules: snapshot.fileTypeRules ?? [],
   };
 }
 
-export async function setDomainRules(rules: DomainRule[]): Promise<void> {
-  await rulesStore.init();
-  await rulesStore.set(
-    'domainRules',
-    rules.map(rule => ({
-      ...rule,
-      domain: rule.pattern,
-    }))
+async function syncRules<T extends { id: string }>(
+  kind: RuleKind,
+  next: T[],
+  current: T[]
+): Promise<void> {
+  const nextIds = new Set(next.map(rule => rule.id));
+  for (const rule of current) {
+    if (!nextIds.has(rule.id)) {
+      await invokeRules('delete_rule', { id: rule.id });
+    }
+  }
+
+  const existing = new Map(
+    current.map(rule => [rule.id, JSON.stringify(rule)])
   );
-  await rulesStore.save();
+  for (const rule of next) {
+    if (existing.get(rule.id) !== JSON.stringify(rule)) {
+      await invokeRules('upsert_rule', { rule: { kind, ...rule } });
+    }
+  }
+
+  await invokeRules('reorder_rules', {
+    kind,
+    ids: next.map(rule => rule.id),
+  });
+}
+
+export type RuleLintCode =
+  | 'shadowed'
+  | 'duplicate'
+  | 'conflict'
+  | 'invalid'
+  | 'missingBrowser'
+  | 'missingProfile';
+
+export type RuleLint = {
+  ruleId: string;
+  kind: RuleKind;
+  code: RuleLintCode;
+  message: string;
+  relatedRuleId?: string;
+};
+
+export async function lintRules(): Promise<RuleLint[]> {
+  return invokeRules<RuleLint[]>('lint_rules');
+}
+
+export type RuleStatsEntry = {
+  ruleId: string;
+  kind: RuleKind;
+  count: number;
+  lastMatchedAt: string | null;
+  lastMatchedUrl: string | null;
+};
+
+export async function fetchRuleStats(): Promise<RuleStatsEntry[]> {
+  return invokeRules<RuleStatsEntry[]>('rule_stats');
+}
+
+export async function setDomainRules(rules: DomainRule[]): Promise<void> {
+  const current = await loadRules();
+  await syncRules('domain', rules, current.domainRules);
 }
 
 export async function setFileTypeRules(rules: FileTypeRule[]): Promise<void> {
-  await rulesStore.init();
-  await rulesStore.set('fileTypeRules', rules);
-  await rulesStore.save();
+  const current = await loadRules();
+  await syncRules('fileType', rules, current.fileTypeRules);
 }
 
 export async function saveRules(snapshot: RulesSnapshot): Promise<void> {
-  await rulesStore.init();
-  await rulesStore.set(
-    'domainRules',
-    snapshot.domainRules.map(rule => ({
-      ...rule,
-      domain: rule.pattern,
-    }))
-  );
-  await rulesStore.set('fileTypeRules', snapshot.fileTypeRules);
-  await rulesStore.save();
+  const current = await loadRules();
+  await syncRules('domain', snapshot.domainRules, current.domainRules);
+  await syncRules('fileType', snapshot.fileTypeRules, current.fileTypeRules);
 }
 
 function normalizeDomainRule(raw: Record<string, unknown>): DomainRule {
@@ -210,6 +293,14 @@ function normalizeDomainRule(raw: Record<string, unknown>): DomainRule {
     );
   }
 
+  const conditions = Array.isArray(raw.conditions)
+    ? (raw.conditions as RuleCondition[])
+    : [];
+
+  const rewrite = typeof raw.rewrite === 'string' ? raw.rewrite.trim() : '';
+  const workspace =
+    typeof raw.workspace === 'string' ? raw.workspace.trim() : '';
+
   return {
     id,
     pattern,
@@ -220,5 +311,8 @@ function normalizeDomainRule(raw: Record<string, unknown>): DomainRule {
     latency,
     enabled,
     domain: pattern,
+    ...(rewrite ? { rewrite } : {}),
+    ...(workspace ? { workspace } : {}),
+    ...(conditions.length > 0 ? { conditions } : {}),
   };
 }
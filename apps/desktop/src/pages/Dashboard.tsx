@@ -129,6 +129,11 @@ export default function Dashboard({
                 <p className='text-sm text-zinc-300'>
                   {activeLink.contactName} • {activeLink.sourceApp}
                 </p>
+                {activeLink.warning ? (
+                  <p className='text-xs text-amber-300' role='alert'>
+                    {activeLink.warning.message}
+                  </p>
+                ) : null}
                 {activeLink.sourceContext ? (
                   <p className='text-xs text-zinc-500'>
                     {activeLink.sourceContext}